@@ -10,6 +10,8 @@ pub enum ParseError {
     NoBadDeeds,
     InvalidGoodDeeds,
     InvalidBadDeeds,
+    MissingColumn(String),
+    UnknownColumn(String),
 }
 
 // When errors displayed, they should be human-readable:
@@ -32,6 +34,8 @@ impl Display for ParseError {
             ParseError::NoBadDeeds => write!(f, "Bad deeds field is missing"),
             ParseError::InvalidGoodDeeds => write!(f, "Good deeds value is invalid"),
             ParseError::InvalidBadDeeds => write!(f, "Bad deeds value is invalid"),
+            ParseError::MissingColumn(column) => write!(f, "Header is missing the '{}' column", column),
+            ParseError::UnknownColumn(column) => write!(f, "Header has an unknown column '{}'", column),
         }
     }
 }
@@ -39,6 +43,9 @@ impl Display for ParseError {
 pub struct Kid {
     pub name: String,
     pub niceness: Niceness,
+    // The raw counts, kept so a Kid can be written back out (Naughty alone doesn't say how many)
+    pub good_deeds: u32,
+    pub bad_deeds: u32,
 }
 
 impl Kid {
@@ -49,7 +56,23 @@ impl Kid {
             Niceness::Naughty
         };
 
-        Kid { name, niceness }
+        Kid {
+            name,
+            niceness,
+            good_deeds,
+            bad_deeds,
+        }
+    }
+
+    // Writes the kid back out as `name,good_deeds,bad_deeds`, the format parse_row reads
+    pub fn to_csv_row(&self) -> String {
+        let name = if self.name.contains([',', '"']) {
+            format!("\"{}\"", self.name.replace('"', "\"\""))
+        } else {
+            self.name.clone()
+        };
+
+        format!("{},{},{}", name, self.good_deeds, self.bad_deeds)
     }
 
     pub fn parse_row(csv_row: &str) -> Result<Kid, ParseError> {
@@ -59,9 +82,15 @@ impl Kid {
         if csv_row.is_empty() {
             return Err(ParseError::NoName);
         }
-        // we need to split the csv_row by commas
-        let mut fields = csv_row.split(',');
-        let name_str = fields.next().ok_or(ParseError::NoName)?;
+        // we need to split the csv_row by commas (but not the ones inside quotes)
+        let fields = split_csv_fields(csv_row);
+        let field = |index: usize| fields.get(index).map(String::as_str);
+        Kid::from_fields(field(0), field(1), field(2))
+    }
+
+    // Validates the three fields, whichever column of the row they came from
+    fn from_fields(name: Option<&str>, good_deeds: Option<&str>, bad_deeds: Option<&str>) -> Result<Kid, ParseError> {
+        let name_str = name.ok_or(ParseError::NoName)?;
         let name = name_str.to_string();
         
         // Check if name is empty after trimming whitespace
@@ -69,41 +98,337 @@ impl Kid {
             return Err(ParseError::NoName);
         }
         // Get good_deeds field and check if it's empty
-        let good_deeds_str = fields.next().ok_or(ParseError::NoGoodDeeds)?;
+        let good_deeds_str = good_deeds.ok_or(ParseError::NoGoodDeeds)?;
         if good_deeds_str.trim().is_empty() {
             return Err(ParseError::NoGoodDeeds);
         }
-        let good_deeds = good_deeds_str.parse::<u32>().map_err(|_| ParseError::InvalidGoodDeeds)?;
+        let good_deeds = parse_count(good_deeds_str).map_err(|_| ParseError::InvalidGoodDeeds)?;
         
         // Get bad_deeds field and check if it's empty
-        let bad_deeds_str = fields.next().ok_or(ParseError::NoBadDeeds)?;
+        let bad_deeds_str = bad_deeds.ok_or(ParseError::NoBadDeeds)?;
         if bad_deeds_str.trim().is_empty() {
             return Err(ParseError::NoBadDeeds);
         }
-        let bad_deeds = bad_deeds_str.parse::<u32>().map_err(|_| ParseError::InvalidBadDeeds)?;
+        let bad_deeds = parse_count(bad_deeds_str).map_err(|_| ParseError::InvalidBadDeeds)?;
 
         Ok(Kid::new(name, good_deeds, bad_deeds))
     }
 
+    // Parses every row of a CSV, tagging each failure with its 1-based line number
+    // Blank lines are skipped rather than reported as a missing name
+    pub fn parse_rows(csv: &str) -> Vec<Result<Kid, (usize, ParseError)>> {
+        csv.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| Kid::parse_row(line).map_err(|e| (index + 1, e)))
+            .collect()
+    }
+
+    // Parses a CSV whose first line names the columns, in any order:
+    //   good_deeds,name,bad_deeds
+    //   3,Alice,1
+    pub fn parse_with_header(csv: &str) -> Result<Vec<Kid>, ParseError> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next().ok_or_else(|| ParseError::MissingColumn("name".to_string()))?;
+
+        let (mut name, mut good, mut bad) = (None, None, None);
+        for (index, column) in header.split(',').enumerate() {
+            match column.trim() {
+                "name" => name = Some(index),
+                "good_deeds" => good = Some(index),
+                "bad_deeds" => bad = Some(index),
+                other => return Err(ParseError::UnknownColumn(other.to_string())),
+            }
+        }
+        let name = name.ok_or_else(|| ParseError::MissingColumn("name".to_string()))?;
+        let good = good.ok_or_else(|| ParseError::MissingColumn("good_deeds".to_string()))?;
+        let bad = bad.ok_or_else(|| ParseError::MissingColumn("bad_deeds".to_string()))?;
+
+        lines
+            .map(|line| {
+                let fields = split_csv_fields(line);
+                let field = |index: usize| fields.get(index).map(String::as_str);
+                Kid::from_fields(field(name), field(good), field(bad))
+            })
+            .collect()
+    }
+
     pub fn is_nice(good_deeds: u32, bad_deeds: u32) -> bool {
+        Self::is_nice_with(&NicenessPolicy::default(), good_deeds, bad_deeds)
+    }
+
+    // Same as is_nice, but with the weights and threshold taken from `policy`
+    // A kid with no deeds at all (0 good, 0 bad) is always Naughty, whatever the policy
+    pub fn is_nice_with(policy: &NicenessPolicy, good_deeds: u32, bad_deeds: u32) -> bool {
         if good_deeds == 0 && bad_deeds == 0 {
             return false;
         }
 
-        let good_deeds = good_deeds as f32 * GOOD_WEIGHT;
-        let bad_deeds = bad_deeds as f32 * BAD_WEIGHT;
+        let good_deeds = good_deeds as f32 * policy.good_weight;
+        let bad_deeds = bad_deeds as f32 * policy.bad_weight;
 
         let ratio = good_deeds / (good_deeds + bad_deeds);
 
-        ratio >= 0.75
+        ratio >= policy.threshold
+    }
+}
+
+// The scoring rule for is_nice_with: a kid is nice when
+//   (good * good_weight) / (good * good_weight + bad * bad_weight) >= threshold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NicenessPolicy {
+    pub good_weight: f32,
+    pub bad_weight: f32,
+    pub threshold: f32,
+}
+
+impl Default for NicenessPolicy {
+    fn default() -> Self {
+        NicenessPolicy {
+            good_weight: GOOD_WEIGHT,
+            bad_weight: BAD_WEIGHT,
+            threshold: NICE_THRESHOLD,
+        }
+    }
+}
+
+// Parses a deed count, also accepting the whole-number floats spreadsheets export:
+//   "5"   -> Ok(5)
+//   "5.0" -> Ok(5)
+//   "5.5" -> Err(())   (half a deed isn't a count)
+//   "abc" -> Err(())
+// Negative, NaN and out-of-range values are rejected too
+fn parse_count(s: &str) -> Result<u32, ()> {
+    if let Ok(count) = s.parse::<u32>() {
+        return Ok(count);
+    }
+
+    let value = s.parse::<f64>().map_err(|_| ())?;
+    if value.fract() != 0.0 || value < 0.0 || value > u32::MAX as f64 {
+        return Err(());
+    }
+    Ok(value as u32)
+}
+
+// Splits a CSV row on commas, honoring double-quoted fields:
+//   "Smith, Jr.",3,1      -> ["Smith, Jr.", "3", "1"]
+//   "Say ""hi""",3,1      -> ["Say \"hi\"", "3", "1"]   ("" inside quotes is one ")
+pub fn split_csv_fields(row: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = row.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+// "Alice (Nice: 5 good deeds)" or "Bob (Naughty)"
+impl Display for Kid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.niceness {
+            Niceness::Nice(good_deeds) => write!(f, "{} (Nice: {} good deeds)", self.name, good_deeds),
+            Niceness::Naughty => write!(f, "{} (Naughty)", self.name),
+        }
+    }
+}
+
+// Kid::try_from("Alice,9,1") and "Alice,9,1".try_into() - both go through parse_row:
+//   let kid: Result<Kid, ParseError> = "Alice,9,1".try_into();   // Ok(Alice (Nice: 9 good deeds))
+//   let kid = Kid::try_from("Alice,five,1");                     // Err(InvalidGoodDeeds)
+impl TryFrom<&str> for Kid {
+    type Error = ParseError;
+
+    fn try_from(csv_row: &str) -> Result<Self, Self::Error> {
+        Kid::parse_row(csv_row)
+    }
+}
+
+// Totals for a whole roster:
+//   [Alice 9/1 (Nice), Bob 1/3 (Naughty), Cara 3/0 (Nice)]
+//     -> RosterSummary { nice: 2, naughty: 1, avg_good_deeds_of_nice: 6.0 }   ((9 + 3) / 2)
+//   []  -> RosterSummary { nice: 0, naughty: 0, avg_good_deeds_of_nice: 0.0 }  (not NaN)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RosterSummary {
+    pub nice: usize,
+    pub naughty: usize,
+    pub avg_good_deeds_of_nice: f32,
+}
+
+pub fn summarize(kids: &[Kid]) -> RosterSummary {
+    let mut nice = 0;
+    let mut good_deeds_of_nice = 0;
+
+    for kid in kids {
+        if let Niceness::Nice(good_deeds) = kid.niceness {
+            nice += 1;
+            good_deeds_of_nice += good_deeds as u64;
+        }
+    }
+
+    // No nice kids means nothing to average - 0 / 0 would be NaN
+    let avg_good_deeds_of_nice = if nice == 0 {
+        0.0
+    } else {
+        good_deeds_of_nice as f32 / nice as f32
+    };
+
+    RosterSummary {
+        nice,
+        naughty: kids.len() - nice,
+        avg_good_deeds_of_nice,
     }
 }
 
 pub const GOOD_WEIGHT: f32 = 1.0;
 pub const BAD_WEIGHT: f32 = 2.0;
+pub const NICE_THRESHOLD: f32 = 0.75;
 
 #[derive(Debug, PartialEq)]
 pub enum Niceness {
     Nice(u32),
     Naughty,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rows_tags_errors_with_line_numbers_and_skips_blank_lines() {
+        let results = Kid::parse_rows("Alice,9,1\n\nBob,x,1\nCara,3,0\n");
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err((3, ParseError::InvalidGoodDeeds))));
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn parse_with_header_reads_columns_in_any_order() {
+        let kids = Kid::parse_with_header("good_deeds,name,bad_deeds\n3,Alice,1\n\n9,Bob,0\n").ok().unwrap();
+        let names: Vec<&str> = kids.iter().map(|kid| kid.name.as_str()).collect();
+        assert_eq!(names, ["Alice", "Bob"]);
+        assert_eq!((kids[0].good_deeds, kids[0].bad_deeds), (3, 1));
+    }
+
+    #[test]
+    fn parse_with_header_rejects_bad_headers() {
+        assert!(matches!(
+            Kid::parse_with_header("name,good_deeds\nAlice,3"),
+            Err(ParseError::MissingColumn(column)) if column == "bad_deeds"
+        ));
+        assert!(matches!(
+            Kid::parse_with_header("name,good_deeds,bad_deeds,age\nAlice,3,1,7"),
+            Err(ParseError::UnknownColumn(column)) if column == "age"
+        ));
+        assert!(matches!(
+            Kid::parse_with_header(""),
+            Err(ParseError::MissingColumn(column)) if column == "name"
+        ));
+        assert_eq!(
+            ParseError::MissingColumn("name".to_string()).to_string(),
+            "Header is missing the 'name' column"
+        );
+    }
+
+    #[test]
+    fn quoted_fields_keep_their_commas_and_escaped_quotes() {
+        assert_eq!(split_csv_fields("\"Smith, Jr.\",3,1"), ["Smith, Jr.", "3", "1"]);
+        assert_eq!(split_csv_fields("\"Say \"\"hi\"\"\",3,1"), ["Say \"hi\"", "3", "1"]);
+
+        // Kid isn't Debug, so unwrap() the Option from .ok() rather than the Result
+        let quoted = Kid::parse_row("\"Smith, Jr.\",3,1").ok().unwrap();
+        assert_eq!(quoted.name, "Smith, Jr.");
+    }
+
+    #[test]
+    fn default_policy_matches_is_nice() {
+        // 6 / (6 + 1 * 2) = 0.75 - exactly on the threshold counts as nice
+        assert!(Kid::is_nice(6, 1));
+        assert!(!Kid::is_nice(3, 1)); // 3 / 5 = 0.6
+        assert!(!Kid::is_nice(0, 0)); // no deeds at all
+        assert_eq!(
+            NicenessPolicy::default(),
+            NicenessPolicy { good_weight: GOOD_WEIGHT, bad_weight: BAD_WEIGHT, threshold: NICE_THRESHOLD }
+        );
+    }
+
+    #[test]
+    fn a_custom_policy_changes_the_verdict() {
+        let strict = NicenessPolicy { good_weight: 1.0, bad_weight: 3.0, threshold: 0.9 };
+        let lenient = NicenessPolicy { good_weight: 1.0, bad_weight: 1.0, threshold: 0.5 };
+        // The same kid, 3 good deeds and 1 bad: 3 / 6 = 0.5 strict, 3 / 4 = 0.75 lenient
+        assert!(!Kid::is_nice_with(&strict, 3, 1));
+        assert!(Kid::is_nice_with(&lenient, 3, 1));
+        assert!(Kid::is_nice_with(&lenient, 1, 1)); // 1 / 2 = 0.5
+        assert!(!Kid::is_nice_with(&lenient, 0, 0)); // still naughty, whatever the policy
+    }
+
+    #[test]
+    fn to_csv_row_round_trips_through_parse_row() {
+        let kid = Kid::parse_row("Alice,9,1").ok().unwrap();
+        assert_eq!((kid.name.as_str(), kid.good_deeds, kid.bad_deeds), ("Alice", 9, 1));
+        assert_eq!(kid.to_string(), "Alice (Nice: 9 good deeds)");
+
+        for row in ["Alice,9,1", "Bob,1,3", "\"Smith, Jr.\",3,1", "\"Say \"\"hi\"\"\",3,1"] {
+            assert_eq!(Kid::parse_row(row).ok().unwrap().to_csv_row(), row);
+        }
+    }
+
+    #[test]
+    fn try_from_goes_through_parse_row() {
+        let kid: Result<Kid, ParseError> = "Alice,9,1".try_into();
+        assert_eq!(kid.ok().unwrap().to_string(), "Alice (Nice: 9 good deeds)");
+        assert!(matches!(Kid::try_from("Alice,five,1"), Err(ParseError::InvalidGoodDeeds)));
+        assert_eq!(Kid::try_from("Bob,1,3").ok().unwrap().to_string(), "Bob (Naughty)");
+    }
+
+    #[test]
+    fn summarize_counts_and_averages_the_nice_kids() {
+        let kids = [Kid::new("Alice".into(), 9, 1), Kid::new("Bob".into(), 1, 3), Kid::new("Cara".into(), 3, 0)];
+        assert_eq!(
+            summarize(&kids),
+            RosterSummary { nice: 2, naughty: 1, avg_good_deeds_of_nice: 6.0 }
+        );
+        assert_eq!(
+            summarize(&[]),
+            RosterSummary { nice: 0, naughty: 0, avg_good_deeds_of_nice: 0.0 }
+        );
+    }
+
+    #[test]
+    fn parse_count_accepts_whole_floats_only() {
+        assert_eq!(parse_count("5"), Ok(5));
+        assert_eq!(parse_count("5.0"), Ok(5));
+        assert_eq!(parse_count("5.5"), Err(()));
+        assert_eq!(parse_count("abc"), Err(()));
+        assert_eq!(parse_count("-1"), Err(()));
+        assert_eq!(parse_count("NaN"), Err(()));
+        assert_eq!(parse_count("1e10"), Err(())); // bigger than u32::MAX
+    }
+
+    #[test]
+    fn from_fields_reports_the_first_bad_field() {
+        assert!(matches!(Kid::parse_row(""), Err(ParseError::NoName)));
+        assert!(matches!(Kid::parse_row("  ,1,1"), Err(ParseError::NoName)));
+        assert!(matches!(Kid::parse_row("Alice"), Err(ParseError::NoGoodDeeds)));
+        assert!(matches!(Kid::parse_row("Alice, ,1"), Err(ParseError::NoGoodDeeds)));
+        assert!(matches!(Kid::parse_row("Alice,five,1"), Err(ParseError::InvalidGoodDeeds)));
+        assert!(matches!(Kid::parse_row("Alice,5.5,1"), Err(ParseError::InvalidGoodDeeds)));
+        assert!(matches!(Kid::parse_row("Alice,5"), Err(ParseError::NoBadDeeds)));
+        assert!(matches!(Kid::parse_row("Alice,5,-1"), Err(ParseError::InvalidBadDeeds)));
+        assert_eq!(Kid::parse_row("Alice,5.0,1").ok().unwrap().good_deeds, 5);
+    }
+}
@@ -1,5 +1,11 @@
 // Write a function that returns the reference to the longer string
 // without any new allocations
+// Compares the TRIMMED strings by character count (chars, not bytes):
+//   longer_wish("apple", "fig")      -> Some("apple")
+//   longer_wish("  hi  ", "hey")     -> Some("hey")   (whitespace doesn't count)
+//   longer_wish("café", "hi")        -> Some("café")  (4 chars, even though it's 5 bytes)
+//   longer_wish("café", "cafe")      -> None          (4 chars each - a tie)
+//   longer_wish("", "   ")           -> None          (both empty after trimming)
 pub fn longer_wish<'a>(s1: &'a str, s2: &'a str) -> Option<&'a str> {
     let s1_trimmed = s1.trim();
     let s2_trimmed = s2.trim();
@@ -12,3 +18,90 @@ pub fn longer_wish<'a>(s1: &'a str, s2: &'a str) -> Option<&'a str> {
         None
     }
 }
+
+// The same idea for any number of strings: the longest by trimmed char count.
+// The first string with the top length wins, unless another string ties it:
+//   longest(&["fig", "apple", "kiwi"])    -> Some("apple")
+//   longest(&["apple", "fig", "mango"])   -> None   (apple and mango tie at 5)
+//   longest(&[])                          -> None
+// With two strings this gives the same answer as longer_wish.
+pub fn longest<'a>(strings: &'a [&'a str]) -> Option<&'a str> {
+    let mut best: Option<(&'a str, usize)> = None;
+    let mut tied = false;
+
+    for s in strings {
+        let trimmed = s.trim();
+        let len = trimmed.chars().count();
+        match best {
+            Some((_, best_len)) if len < best_len => {}
+            Some((_, best_len)) if len == best_len => tied = true,
+            _ => {
+                best = Some((trimmed, len));
+                tied = false;
+            }
+        }
+    }
+
+    match best {
+        Some((s, _)) if !tied => Some(s),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longer_wish_picks_the_clear_winner() {
+        assert_eq!(longer_wish("apple", "fig"), Some("apple"));
+        assert_eq!(longer_wish("fig", "apple"), Some("apple"));
+    }
+
+    #[test]
+    fn longer_wish_ties_and_empties_are_none() {
+        assert_eq!(longer_wish("pear", "plum"), None);
+        assert_eq!(longer_wish("", "   "), None);
+    }
+
+    #[test]
+    fn longer_wish_counts_chars_not_bytes() {
+        // "café" is 4 chars but 5 bytes
+        assert_eq!(longer_wish("café", "hi"), Some("café"));
+        assert_eq!(longer_wish("café", "cafe"), None);
+    }
+
+    #[test]
+    fn longer_wish_trims_before_comparing() {
+        assert_eq!(longer_wish("  hi  ", "hey"), Some("hey"));
+        assert_eq!(longer_wish("  apple ", "fig"), Some("apple"));
+    }
+
+    #[test]
+    fn longest_picks_the_first_string_with_the_unique_top_length() {
+        assert_eq!(longest(&["fig", "apple", "kiwi"]), Some("apple"));
+        // A tie BELOW the top doesn't matter
+        assert_eq!(longest(&["fig", "pear", "kiwi", "mango"]), Some("mango"));
+        // An early tie is broken by a longer string later on
+        assert_eq!(longest(&["pear", "plum", "banana"]), Some("banana"));
+    }
+
+    #[test]
+    fn longest_tie_at_the_top_is_none() {
+        assert_eq!(longest(&["apple", "fig", "mango"]), None);
+        assert_eq!(longest(&["  café ", "cafe"]), None); // 4 chars each after trimming
+    }
+
+    #[test]
+    fn longest_of_nothing_is_none() {
+        assert_eq!(longest(&[]), None);
+        assert_eq!(longest(&["only"]), Some("only"));
+    }
+
+    #[test]
+    fn longest_agrees_with_longer_wish_for_two_strings() {
+        for (a, b) in [("apple", "fig"), ("hi", "café"), ("pear", "plum"), ("", " ")] {
+            assert_eq!(longest(&[a, b]), longer_wish(a, b));
+        }
+    }
+}
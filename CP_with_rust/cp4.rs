@@ -1,24 +1,40 @@
-use std::io::{self, Write};
+use std::io::{self, BufRead, Write};
 
-fn main() {
-    print!("Enter first string: ");
-    io::stdout().flush().unwrap(); // Force output to show
-    
-    let mut s1 = String::new();
-    io::stdin().read_line(&mut s1).unwrap();
-    
-    print!("Enter second string: ");
-    io::stdout().flush().unwrap();
-    
-    let mut s2 = String::new();
-    io::stdin().read_line(&mut s2).unwrap();
+fn main() -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut out = io::stdout();
+
+    let s1 = prompt("Enter first string: ", &mut out, &mut input)?;
+    let s2 = prompt("Enter second string: ", &mut out, &mut input)?;
     
     match longer_wish(&s1, &s2) {
-        Some(longer) => println!("Longer string: '{}'", longer.trim()),
+        Some(longer) => println!("Longer string: '{}'", longer),
         None => println!("Strings are equal length or both empty"),
     }
+    Ok(())
+}
+
+// Shows `label`, reads one line, and returns it trimmed:
+//   prompt("Enter first string: ", &mut io::stdout(), &mut io::stdin().lock())
+// The label is flushed first - without a newline, stdout would hold it back
+// until after the user had typed. Any writer/reader works, so a test can pass
+// a Vec<u8> and a Cursor instead of the terminal.
+pub fn prompt<R: BufRead, W: Write>(label: &str, out: &mut W, input: &mut R) -> io::Result<String> {
+    write!(out, "{}", label)?;
+    out.flush()?;
+
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    Ok(line.trim().to_string())
 }
 
+// Compares the TRIMMED strings by character count (chars, not bytes):
+//   longer_wish("apple", "fig")      -> Some("apple")
+//   longer_wish("  hi  ", "hey")     -> Some("hey")   (whitespace doesn't count)
+//   longer_wish("café", "hi")        -> Some("café")  (4 chars, even though it's 5 bytes)
+//   longer_wish("café", "cafe")      -> None          (4 chars each - a tie)
+//   longer_wish("", "   ")           -> None          (both empty after trimming)
 pub fn longer_wish<'a>(s1: &'a str, s2: &'a str) -> Option<&'a str> {
     let s1_trimmed = s1.trim();
     let s2_trimmed = s2.trim();
@@ -31,3 +47,45 @@ pub fn longer_wish<'a>(s1: &'a str, s2: &'a str) -> Option<&'a str> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_writes_the_label_and_returns_the_trimmed_line() {
+        // Canned input instead of a keyboard: one line in, label out
+        let mut written = Vec::new();
+        let mut input = io::Cursor::new("  Alice  \nBob\n");
+        assert_eq!(prompt("Name: ", &mut written, &mut input).unwrap(), "Alice");
+        assert_eq!(written, b"Name: ");
+
+        // The next prompt carries on with the next line
+        assert_eq!(prompt("Again: ", &mut written, &mut input).unwrap(), "Bob");
+        assert_eq!(written, b"Name: Again: ");
+    }
+
+    #[test]
+    fn longer_wish_picks_the_clear_winner() {
+        assert_eq!(longer_wish("apple", "fig"), Some("apple"));
+        assert_eq!(longer_wish("fig", "apple"), Some("apple"));
+    }
+
+    #[test]
+    fn longer_wish_ties_and_empties_are_none() {
+        assert_eq!(longer_wish("pear", "plum"), None);
+        assert_eq!(longer_wish("", "   "), None);
+    }
+
+    #[test]
+    fn longer_wish_counts_chars_not_bytes() {
+        // "café" is 4 chars but 5 bytes
+        assert_eq!(longer_wish("café", "hi"), Some("café"));
+        assert_eq!(longer_wish("café", "cafe"), None);
+    }
+
+    #[test]
+    fn longer_wish_trims_before_comparing() {
+        assert_eq!(longer_wish("  hi  ", "hey"), Some("hey"));
+    }
+}
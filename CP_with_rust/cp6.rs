@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::ops::Deref;
 use std::error::Error;
 
@@ -36,6 +37,7 @@ impl Deref for SnowLb {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct Snowball(pub i64);
 
 impl Snowball {
@@ -66,6 +68,37 @@ impl From<SnowLb> for Snowball {
     }
 }
 
+// The reverse direction: how much do N snowballs weigh?
+// Going weight -> Snowball -> weight is lossy because the count is rounded to a
+// whole snowball, so a round trip can be off by up to half a snowball:
+// 0.1 kg (SNOWBALL_WEIGHT_KG / 2) or 0.2205 lb (SNOWBALL_WEIGHT_LB / 2).
+impl From<Snowball> for SnowKg {
+    fn from(snowballs: Snowball) -> Self {
+        SnowKg(*snowballs as f64 * SNOWBALL_WEIGHT_KG)
+    }
+}
+
+impl From<Snowball> for SnowLb {
+    fn from(snowballs: Snowball) -> Self {
+        SnowLb(*snowballs as f64 * SNOWBALL_WEIGHT_LB)
+    }
+}
+
+// Plain unit conversion, no snowball rounding involved.
+// The ratio comes from the two snowball weights: 0.441 / 0.2 = 2.205 lb per kg
+// (the exact figure is 2.20462, so this is within 0.02%).
+impl From<SnowKg> for SnowLb {
+    fn from(kg: SnowKg) -> Self {
+        SnowLb(*kg * (SNOWBALL_WEIGHT_LB / SNOWBALL_WEIGHT_KG))
+    }
+}
+
+impl From<SnowLb> for SnowKg {
+    fn from(lb: SnowLb) -> Self {
+        SnowKg(*lb * (SNOWBALL_WEIGHT_KG / SNOWBALL_WEIGHT_LB))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Location {
     pub x: f64,
@@ -93,22 +126,48 @@ impl Location {
             (*self.snow as f64) / self.area
         }
     }
-}
 
-pub fn find_best_location(locations: Vec<Location>) -> Result<Location, Box<dyn Error>> {
-    if locations.is_empty() {
-        return Err("No locations provided".into());
+    // Orders two locations by density, with NaN (e.g. a NaN area) as the lowest
+    pub fn cmp_density(&self, other: &Location) -> Ordering {
+        let (a, b) = (self.density(), other.density());
+        match (a.is_nan(), b.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        }
     }
+}
 
-    let mut best = locations[0].clone();
+// Locations compare by density, so they can be sorted or passed to max_by.
+// f64 isn't Ord (NaN != NaN), so only the Partial* traits are implemented.
+impl PartialEq for Location {
+    fn eq(&self, other: &Self) -> bool {
+        self.density() == other.density()
+    }
+}
 
-    for location in &locations[1..] {
-        if location.density() > best.density() {
-            best = location.clone();
-        }
+impl PartialOrd for Location {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.density().partial_cmp(&other.density())
     }
+}
 
-    Ok(best)
+pub fn find_best_location_index(locations: &[Location]) -> Result<usize, Box<dyn Error>> {
+    // max_by keeps the LAST of equal maximums, so iterate in reverse to keep the first
+    locations
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by(|(_, a), (_, b)| a.cmp_density(b))
+        .map(|(index, _)| index)
+        .ok_or_else(|| "No locations provided".into())
+}
+
+// Kept for existing callers - moves the winner out of the vector instead of cloning it
+pub fn find_best_location(mut locations: Vec<Location>) -> Result<Location, Box<dyn Error>> {
+    let index = find_best_location_index(&locations)?;
+    Ok(locations.swap_remove(index))
 }
 
 fn main() {
@@ -127,3 +186,75 @@ fn main() {
         Err(e) => println!("Error: {}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snowballs_convert_back_to_weight() {
+        assert_eq!(SnowKg::from(Snowball(25)).0, 25.0 * SNOWBALL_WEIGHT_KG);
+        assert_eq!(SnowLb::from(Snowball(10)).0, 10.0 * SNOWBALL_WEIGHT_LB);
+
+        // The round trip is only as good as the rounding to whole snowballs
+        let back = SnowKg::from(Snowball::from(SnowKg(1.05)));
+        assert!((back.0 - 1.05).abs() <= SNOWBALL_WEIGHT_KG / 2.0);
+    }
+
+    #[test]
+    fn kg_and_lb_convert_directly() {
+        assert!((SnowLb::from(SnowKg(1.0)).0 - 2.2046).abs() < 1e-3); // about 2.2046 lb per kg
+        assert!((SnowLb::from(SnowKg(2.0)).0 - 4.41).abs() < 1e-9);
+        assert!((SnowKg::from(SnowLb(4.41)).0 - 2.0).abs() < 1e-9);
+        // Same number of snowballs whichever unit the weight came in
+        assert_eq!(Snowball::from(SnowKg(5.0)).0, Snowball::from(SnowLb::from(SnowKg(5.0))).0);
+    }
+
+    #[test]
+    fn a_nan_area_is_never_the_best_location() {
+        let nan = Location::new(0.0, 0.0, 0.0, f64::NAN, Snowball(100));
+        let real = Location::new(1.0, 2.0, 3.0, 100.0, Snowball(1)); // density 0.01
+        assert!(nan.density().is_nan());
+
+        // Wherever the NaN sits - first, last, or surrounded - the real one wins
+        assert_eq!(find_best_location_index(&[nan.clone(), real.clone()]).unwrap(), 1);
+        assert_eq!(find_best_location_index(&[real.clone(), nan.clone()]).unwrap(), 0);
+        assert_eq!(find_best_location_index(&[nan.clone(), real, nan.clone()]).unwrap(), 1);
+    }
+
+    #[test]
+    fn only_nan_areas_still_pick_one_and_empty_is_an_error() {
+        let nan = Location::new(0.0, 0.0, 0.0, f64::NAN, Snowball(1));
+        assert_eq!(find_best_location_index(&[nan.clone(), nan]).unwrap(), 0);
+        assert!(find_best_location_index(&[]).is_err());
+    }
+
+    #[test]
+    fn equal_densities_keep_the_first() {
+        let twins = [
+            Location::new(1.0, 0.0, 0.0, 10.0, Snowball(5)),
+            Location::new(2.0, 0.0, 0.0, 10.0, Snowball(5)),
+        ];
+        assert_eq!(find_best_location_index(&twins).unwrap(), 0);
+    }
+
+    // The three locations from main: 25 snowballs each, densities 0.25, 0.50, 0.33
+    fn field() -> Vec<Location> {
+        vec![
+            Location::new(1.0, 2.0, 3.0, 100.0, SnowKg(5.0)),
+            Location::new(4.0, 5.0, 6.0, 50.0, SnowLb(11.0)),
+            Location::new(7.0, 8.0, 9.0, 75.0, Snowball(25)),
+        ]
+    }
+
+    #[test]
+    fn best_location_is_found_by_index() {
+        let locations = field();
+        let index = find_best_location_index(&locations).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(locations[index].area, 50.0); // still ours to index into
+
+        let best = find_best_location(locations).unwrap();
+        assert_eq!((best.x, best.y, best.z), (4.0, 5.0, 6.0));
+    }
+}
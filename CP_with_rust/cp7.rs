@@ -2,9 +2,16 @@
 // This example demonstrates the difference between:
 // 1. A struct (single object with fields)
 // 2. A vector (collection of multiple objects)
+//
+// Uses serde for the JSON export, so build it with cargo (see Cargo.toml):
+//   cargo run --bin cp7
 
-use std::ops::Deref;
+use serde::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Deref};
 use std::error::Error;
+use std::fmt;
 
 const SNOWBALL_WEIGHT_KG: f64 = 0.2;
 const SNOWBALL_WEIGHT_LB: f64 = 0.441;
@@ -15,6 +22,19 @@ impl SnowKg {
     pub fn new(kg: f64) -> Self {
         SnowKg(kg)
     }
+
+    // The wrapped kilograms. Deref (*kg) still works and is handy for quick
+    // math like `*kg * 2.0`; value() reads better where ** or (*x as f64) piles up
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+// Lets generic code take "anything that is an f64 underneath": see sum_values
+impl AsRef<f64> for SnowKg {
+    fn as_ref(&self) -> &f64 {
+        &self.0
+    }
 }
 
 impl Deref for SnowKg {
@@ -31,6 +51,17 @@ impl SnowLb {
     pub fn new(lb: f64) -> Self {
         SnowLb(lb)
     }
+
+    // The wrapped pounds - same trade-off as SnowKg::value
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl AsRef<f64> for SnowLb {
+    fn as_ref(&self) -> &f64 {
+        &self.0
+    }
 }
 
 impl Deref for SnowLb {
@@ -41,12 +72,53 @@ impl Deref for SnowLb {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Snowball(pub i64);
 
+// What can go wrong building a Snowball count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnowError {
+    Negative(i64),
+}
+
+impl fmt::Display for SnowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnowError::Negative(n) => write!(f, "snowball count can't be negative (got {})", n),
+        }
+    }
+}
+
+impl Error for SnowError {}
+
 impl Snowball {
     pub fn new(snowballs: i64) -> Self {
         Snowball(snowballs)
     }
+
+    // Like new, but a negative count is an error instead of a nonsense Snowball:
+    //   Snowball::try_new(3)  -> Ok(Snowball(3))
+    //   Snowball::try_new(-3) -> Err(SnowError::Negative(-3))
+    pub fn try_new(snowballs: i64) -> Result<Self, SnowError> {
+        if snowballs < 0 {
+            Err(SnowError::Negative(snowballs))
+        } else {
+            Ok(Snowball(snowballs))
+        }
+    }
+
+    // The wrapped count - same trade-off as SnowKg::value
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+}
+
+// JSON sees a Snowball as its plain number ("snow": 25), not a wrapper -
+// the newtype only exists on the Rust side
+impl Serialize for Snowball {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.0)
+    }
 }
 
 impl Deref for Snowball {
@@ -57,22 +129,102 @@ impl Deref for Snowball {
     }
 }
 
+// Snowball(10) + Snowball(5) == Snowball(15)
+impl Add for Snowball {
+    type Output = Snowball;
+
+    fn add(self, other: Snowball) -> Snowball {
+        Snowball(self.0 + other.0)
+    }
+}
+
+// pile += Snowball(5)
+impl AddAssign for Snowball {
+    fn add_assign(&mut self, other: Snowball) {
+        self.0 += other.0;
+    }
+}
+
+// Lets an iterator of Snowballs be .sum()'d, starting from Snowball(0)
+impl Sum for Snowball {
+    fn sum<I: Iterator<Item = Snowball>>(iter: I) -> Snowball {
+        iter.fold(Snowball(0), Add::add)
+    }
+}
+
 impl From<SnowKg> for Snowball {
     fn from(kg: SnowKg) -> Self {
-        let snowballs = (*kg / SNOWBALL_WEIGHT_KG).round() as i64;
-        Snowball(snowballs)
+        // From can't fail, so a negative weight clamps to zero snowballs
+        let snowballs = (kg.value() / SNOWBALL_WEIGHT_KG).round() as i64;
+        Snowball(snowballs.max(0))
     }
 }
 
 impl From<SnowLb> for Snowball {
     fn from(lb: SnowLb) -> Self {
-        let snowballs = (*lb / SNOWBALL_WEIGHT_LB).round() as i64;
-        Snowball(snowballs)
+        let snowballs = (lb.value() / SNOWBALL_WEIGHT_LB).round() as i64;
+        Snowball(snowballs.max(0))
+    }
+}
+
+// The reverse direction: how much do N snowballs weigh?
+// Going weight -> Snowball -> weight is lossy because the count is rounded to a
+// whole snowball, so a round trip can be off by up to half a snowball:
+// 0.1 kg (SNOWBALL_WEIGHT_KG / 2) or 0.2205 lb (SNOWBALL_WEIGHT_LB / 2).
+impl From<Snowball> for SnowKg {
+    fn from(snowballs: Snowball) -> Self {
+        SnowKg(snowballs.value() as f64 * SNOWBALL_WEIGHT_KG)
     }
 }
 
+impl From<Snowball> for SnowLb {
+    fn from(snowballs: Snowball) -> Self {
+        SnowLb(snowballs.value() as f64 * SNOWBALL_WEIGHT_LB)
+    }
+}
+
+// Plain unit conversion, no snowball rounding involved.
+// The ratio comes from the two snowball weights: 0.441 / 0.2 = 2.205 lb per kg
+// (the exact figure is 2.20462, so this is within 0.02%).
+impl From<SnowKg> for SnowLb {
+    fn from(kg: SnowKg) -> Self {
+        SnowLb(*kg * (SNOWBALL_WEIGHT_LB / SNOWBALL_WEIGHT_KG))
+    }
+}
+
+impl From<SnowLb> for SnowKg {
+    fn from(lb: SnowLb) -> Self {
+        SnowKg(*lb * (SNOWBALL_WEIGHT_KG / SNOWBALL_WEIGHT_LB))
+    }
+}
+
+// numerator / denominator, or 0.0 when that division would be meaningless:
+//   safe_ratio(10.0, 4.0)       == 2.5
+//   safe_ratio(10.0, 0.0)       == 0.0   (not infinity)
+//   safe_ratio(10.0, f64::NAN)  == 0.0   (not NaN)
+// Every derived "per something" metric goes through here, so the guard lives in one place
+pub fn safe_ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 || !denominator.is_finite() {
+        //                   ^^^^^^^^^^^^^^^^^^^^^^^
+        //                   false for NaN, infinity and -infinity
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+// Adds up any weights that are f64s underneath - SnowKg or SnowLb:
+//   sum_values(&[SnowKg(1.5), SnowKg(2.5)])  == 4.0
+//   sum_values(&[SnowLb(1.0), SnowLb(0.5)])  == 1.5
+pub fn sum_values<T: AsRef<f64>>(values: &[T]) -> f64 {
+    //            ^^^^^^^^^^^^^
+    //            "T can lend out a &f64" - plain f64 doesn't implement AsRef<f64>,
+    //            so it needs a wrapper like these
+    values.iter().map(|value| *value.as_ref()).sum()
+}
+
 // This is ONE Location (a struct with fields)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Location {
     pub x: f64,
     pub y: f64,
@@ -82,6 +234,11 @@ pub struct Location {
 }
 
 impl Location {
+    // Start here for named fields instead of five positional arguments
+    pub fn builder() -> LocationBuilder {
+        LocationBuilder::default()
+    }
+
     pub fn new<T: Into<Snowball>>(x: f64, y: f64, z: f64, area: f64, snow: T) -> Location {
         Location {
             x,
@@ -92,47 +249,254 @@ impl Location {
         }
     }
 
+    // Snow per unit of area - 0.0 for a zero (or NaN/infinite) area
     pub fn density(&self) -> f64 {
-        if self.area == 0.0 {
-            0.0
-        } else {
-            (*self.snow as f64) / self.area
+        safe_ratio(self.snow.value() as f64, self.area)
+    }
+
+    // Snow per unit of x * y * z - 0.0 when any side is zero (or the product isn't finite)
+    pub fn snow_per_unit_volume(&self) -> f64 {
+        safe_ratio(self.snow.value() as f64, self.x * self.y * self.z)
+    }
+
+    // A copy of this location with a different snow amount; `self` is left as it was
+    //   let deeper = spot.with_snow(SnowKg(3.0));   // spot.snow unchanged, deeper.snow == Snowball(15)
+    pub fn with_snow<T: Into<Snowball>>(&self, snow: T) -> Location {
+        Location {
+            snow: snow.into(),
+            ..self.clone()
+            // ^^^^^^^^^^^
+            // Struct update syntax: every field not listed above comes from the clone
+        }
+    }
+
+    // Straight-line 3D distance: sqrt(dx² + dy² + dz²)
+    pub fn distance_to(&self, other: &Location) -> f64 {
+        let (dx, dy, dz) = (self.x - other.x, self.y - other.y, self.z - other.z);
+        (dx * dx + dy * dy + dz * dz).sqrt()
+    }
+
+    // The closest location in `others`, or None if there is nothing to compare to
+    pub fn nearest<'a>(&self, others: &'a [Location]) -> Option<&'a Location> {
+        //         ^^                 ^^                ^^
+        //         The result borrows from `others`, not from `self`
+        others
+            .iter()
+            .filter(|other| !std::ptr::eq(*other, self))
+            //               ^^^^^^^^^^^^^^^^^^^^^^^^^^
+            //               Skip `self` if it's in the slice (same address, not just equal values)
+            .min_by(|a, b| {
+                self.distance_to(a)
+                    .partial_cmp(&self.distance_to(b))
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+
+    // Orders two locations by density, with NaN as the lowest
+    // (density() itself never returns NaN, but this stays a total order regardless)
+    pub fn cmp_density(&self, other: &Location) -> Ordering {
+        let (a, b) = (self.density(), other.density());
+        match (a.is_nan(), b.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
         }
     }
+
+    // Like ==, but treats coordinates/area within `epsilon` of each other as equal
+    // Handy in tests, where computed floats are rarely bit-for-bit identical
+    pub fn approx_eq(&self, other: &Location, epsilon: f64) -> bool {
+        (self.x - other.x).abs() <= epsilon
+            && (self.y - other.y).abs() <= epsilon
+            && (self.z - other.z).abs() <= epsilon
+            && (self.area - other.area).abs() <= epsilon
+            && self.snow == other.snow
+    }
 }
 
-// This function receives MULTIPLE Locations (a vector)
-pub fn find_best_location(locations: Vec<Location>) -> Result<Location, Box<dyn Error>> {
-    // locations = a vector/array containing many Location objects
-    // locations[0] = get the first Location from the vector
-    // locations[1] = get the second Location from the vector
+// Builds a Location one named field at a time:
+//   Location::builder().x(1.0).z(3.0).area(100.0).snow(SnowKg(5.0)).build()
+// Anything not set is 0 (coordinates, area and snow)
+#[derive(Debug, Default)]
+pub struct LocationBuilder {
+    x: f64,
+    y: f64,
+    z: f64,
+    area: f64,
+    snow: Option<Snowball>,
+}
+
+impl LocationBuilder {
+    pub fn x(mut self, x: f64) -> Self {
+    //       ^^^^^^^^           ^^^^
+    //       Takes the builder by value and hands it back, so calls can be chained
+        self.x = x;
+        self
+    }
+
+    pub fn y(mut self, y: f64) -> Self {
+        self.y = y;
+        self
+    }
 
-    if locations.is_empty() {
-        return Err("No locations provided".into());
+    pub fn z(mut self, z: f64) -> Self {
+        self.z = z;
+        self
     }
 
-    // Get the FIRST Location from the vector
-    let mut best = locations[0].clone();
-    //             ^^^^^^^^^^^^
-    //             Index into the vector to get Location at index 0
+    pub fn area(mut self, area: f64) -> Self {
+        self.area = area;
+        self
+    }
+
+    pub fn snow<T: Into<Snowball>>(mut self, snow: T) -> Self {
+        self.snow = Some(snow.into());
+        self
+    }
 
-    // Loop through the REST of the locations (from index 1 onwards)
-    for location in &locations[1..] {
-    //  ^^^^^^^^    ^^^^^^^^^^^^^^
-    //  Each item   Slice from index 1 to end
-        if location.density() > best.density() {
-            best = location.clone();
+    pub fn build(self) -> Location {
+        Location::new(self.x, self.y, self.z, self.area, self.snow.unwrap_or(Snowball(0)))
+    }
+}
+
+// Two locations are equal when every field is equal.
+// Written by hand (not derived) to sit next to the hand-written PartialOrd below.
+// There's no Eq: f64 isn't Eq (NaN != NaN), so a Location holding NaN isn't
+// even equal to itself. Use approx_eq for computed values.
+impl PartialEq for Location {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x
+            && self.y == other.y
+            && self.z == other.z
+            && self.area == other.area
+            && self.snow == other.snow
+    }
+}
+
+// Locations sort by density, so they can be sorted or passed to max_by.
+// Ties fall back to the fields, so partial_cmp only says Equal when == does.
+impl PartialOrd for Location {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.density().partial_cmp(&other.density()) {
+            Some(Ordering::Equal) => {}
+            ordering => return ordering,
         }
+
+        for (a, b) in [
+            (self.x, other.x),
+            (self.y, other.y),
+            (self.z, other.z),
+            (self.area, other.area),
+        ] {
+            match a.partial_cmp(&b) {
+                Some(Ordering::Equal) => {}
+                ordering => return ordering,
+            }
+        }
+
+        self.snow.partial_cmp(&other.snow)
+    }
+}
+
+// This function BORROWS a slice of Locations and returns a POSITION (index)
+pub fn find_best_location_index(locations: &[Location]) -> Result<usize, Box<dyn Error>> {
+    //                                    ^^^^^^^^^^^                ^^^^^
+    //                                    A borrowed slice:          Just a number -
+    //                                    works with a Vec, an       callers can index
+    //                                    array, or part of either   back in: locations[i]
+
+    // Instead of looping by hand, let the iterator find the maximum for us
+    locations
+        .iter()
+    //   ^^^^^^
+    //   Borrows each Location - nothing is cloned or moved
+        .enumerate()
+    //   ^^^^^^^^^^^
+    //   Pairs each Location with its index: (0, &loc0), (1, &loc1), ...
+        .rev()
+    //   ^^^^^
+    //   max_by keeps the LAST of equal maximums, reversing keeps the first one
+        .max_by(|(_, a), (_, b)| a.cmp_density(b))
+    //          ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    //          Compare by density (ignoring the index); a NaN density always loses
+        .map(|(index, _)| index)
+    //   ^^^^^^^^^^^^^^^^^^^^^^^
+    //   Keep only the index of the winner
+        .ok_or_else(|| "No locations provided".into())
+    //   ^^^^^^^^^^
+    //   max_by returns None for an empty slice - turn that into our error
+}
+
+// This function receives MULTIPLE Locations (a vector) and returns the best ONE
+// Kept for existing callers - it's now a thin wrapper around the index version
+pub fn find_best_location(mut locations: Vec<Location>) -> Result<Location, Box<dyn Error>> {
+    let index = find_best_location_index(&locations)?;
+    //                                   ^^^^^^^^^^
+    //                                   &Vec<Location> turns into &[Location] automatically
+
+    Ok(locations.swap_remove(index))
+    // ^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    // MOVES the winner out of the vector (no clone) - we own the vector anyway
+}
+
+// Every location, best (densest) first - references, so nothing is cloned or moved
+// NaN densities go last, like Location::cmp_density treats them as the lowest
+pub fn sorted_by_density(locations: &[Location]) -> Vec<&Location> {
+    let mut sorted: Vec<&Location> = locations.iter().collect();
+    sorted.sort_by(|a, b| b.cmp_density(a));
+    //              ^^^^^^^^^^^^^^^^^^^^^^
+    //              b vs a (not a vs b) flips it to descending; cmp_density does the partial_cmp
+    sorted
+}
+
+// Aggregate stats over a whole field of locations
+pub fn total_snow(locations: &[Location]) -> i64 {
+    let total: Snowball = locations.iter().map(|location| location.snow).sum();
+    //                                     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^  ^^^^^
+    //                                     Copies each Snowball out        Adds them with Snowball's Sum impl
+    total.0
+    //   ^^
+    //   Unwrap the newtype back to a plain i64
+}
+
+pub fn average_density(locations: &[Location]) -> f64 {
+    // Zero-area locations report density 0.0 as a placeholder, not a real
+    // measurement, so leave them out instead of dragging the average down
+    let measured: Vec<f64> = locations
+        .iter()
+        .filter(|location| location.area > 0.0)
+        .map(|location| location.density())
+        .collect();
+
+    if measured.is_empty() {
+        return 0.0; // avoid 0.0 / 0.0 = NaN
     }
 
-    Ok(best)
+    measured.iter().sum::<f64>() / measured.len() as f64
+}
+
+// A JSON array of every location, ready to hand to other tools:
+//   [{"area":100.0,"snow":25,"x":1.0,"y":2.0,"z":3.0}, ...]
+// (a Value keeps object keys sorted). JSON has no NaN or infinity, so
+// serde_json writes those as null
+pub fn export_locations(locations: &[Location]) -> serde_json::Value {
+    serde_json::to_value(locations).expect("a Location always serializes")
+    //                                ^^^^^^
+    //                                Only fails for things like non-string map keys - none here
 }
 
 fn main() {
     println!("=== Understanding Vectors vs Structs ===\n");
 
-    // Create ONE Location (a struct)
-    let single_location = Location::new(1.0, 2.0, 3.0, 100.0, SnowKg(5.0));
+    // Create ONE Location (a struct) - the builder names every field
+    let single_location = Location::builder()
+        .x(1.0)
+        .y(2.0)
+        .z(3.0)
+        .area(100.0)
+        .snow(SnowKg(5.0))
+        .build();
     println!("Single Location:");
     println!("  x: {}", single_location.x);  // Access field with .x
     println!("  y: {}", single_location.y);  // Access field with .y
@@ -159,6 +523,38 @@ fn main() {
     println!("    x: {}", locations[2].x);
     println!("    density: {:.2}", locations[2].density());
 
+    // Summarize the whole vector
+    println!("\n=== Field Summary ===");
+    println!("Total snow: {} snowballs", total_snow(&locations));
+    println!("Average density: {:.2}", average_density(&locations));
+    //                                                  ^^^^^^^^^^
+    //                                                  Borrow - we still need the vector below
+
+    println!("Snowball::from(SnowKg(-1.0)) = {:?} (clamped)", Snowball::from(SnowKg(-1.0)));
+    match Snowball::try_new(-3) {
+        Ok(snow) => println!("Snowball::try_new(-3) = {:?}", snow),
+        Err(e) => println!("Snowball::try_new(-3) failed: {}", e),
+    }
+
+    let loads = [SnowKg(1.5), SnowKg(2.5)];
+    println!("Snow delivered: {} kg (value() of the first: {})", sum_values(&loads), loads[0].value());
+
+    let deeper = locations[0].with_snow(SnowKg(3.0));
+    println!("with_snow: original keeps {:?}, the copy has {:?}", locations[0].snow, deeper.snow);
+
+    let ranked = sorted_by_density(&locations);
+    println!("\nBy density, best first:");
+    for location in &ranked {
+        println!("  ({}, {}, {}) density {:.2}", location.x, location.y, location.z, location.density());
+    }
+
+    println!("Snow per unit volume at [0]: {:.2}", locations[0].snow_per_unit_volume());
+
+    println!("\n=== JSON Export ===");
+    println!("{}", export_locations(&locations));
+    //             ^^^^^^^^^^^^^^^^
+    //             A serde_json::Value prints as compact JSON
+
     // Find the best location
     println!("\n=== Finding Best Location ===");
     match find_best_location(locations) {
@@ -169,3 +565,199 @@ fn main() {
         Err(e) => println!("Error: {}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snowballs_convert_back_to_weight() {
+        assert_eq!(SnowKg::from(Snowball(25)).0, 25.0 * SNOWBALL_WEIGHT_KG);
+        assert_eq!(SnowLb::from(Snowball(10)).0, 10.0 * SNOWBALL_WEIGHT_LB);
+
+        // The round trip is only as good as the rounding to whole snowballs
+        let back = SnowKg::from(Snowball::from(SnowKg(1.05)));
+        assert!((back.0 - 1.05).abs() <= SNOWBALL_WEIGHT_KG / 2.0);
+    }
+
+    // The three locations from main: 25 snowballs each
+    fn field() -> Vec<Location> {
+        vec![
+            Location::new(1.0, 2.0, 3.0, 100.0, SnowKg(5.0)),
+            Location::new(4.0, 5.0, 6.0, 50.0, SnowLb(11.0)),
+            Location::new(7.0, 8.0, 9.0, 75.0, Snowball(25)),
+        ]
+    }
+
+    #[test]
+    fn field_totals() {
+        assert_eq!(total_snow(&field()), 75);
+        // (0.25 + 0.50 + 0.333...) / 3
+        assert!((average_density(&field()) - (0.25 + 0.5 + 25.0 / 75.0) / 3.0).abs() < 1e-12);
+        // A zero area is left out of the average rather than counted as 0.0
+        let with_empty = [field()[0].clone(), Location::new(0.0, 0.0, 0.0, 0.0, Snowball(9))];
+        assert_eq!(average_density(&with_empty), 0.25);
+        assert_eq!(total_snow(&[]), 0);
+        assert_eq!(average_density(&[]), 0.0);
+    }
+
+    #[test]
+    fn distance_and_nearest() {
+        let origin = Location::new(0.0, 0.0, 0.0, 1.0, Snowball(0));
+        assert_eq!(origin.distance_to(&Location::new(3.0, 4.0, 0.0, 1.0, Snowball(0))), 5.0);
+        assert_eq!(origin.distance_to(&Location::new(0.0, 0.0, -2.0, 1.0, Snowball(0))), 2.0);
+
+        let locations = field();
+        assert_eq!(origin.nearest(&locations).map(|l| l.x), Some(1.0));
+        assert!(origin.nearest(&[]).is_none());
+        // `self` inside the slice is skipped, not reported as its own nearest
+        assert_eq!(locations[0].nearest(&locations).map(|l| l.x), Some(4.0));
+    }
+
+    #[test]
+    fn equality_is_exact_and_approx_eq_allows_rounding() {
+        let a = Location::new(0.1 + 0.2, 0.0, 0.0, 1.0, Snowball(1));
+        let b = Location::new(0.3, 0.0, 0.0, 1.0, Snowball(1));
+        assert_ne!(a, b); // 0.1 + 0.2 is 0.30000000000000004
+        assert!(a.approx_eq(&b, 1e-9));
+        let more_snow = Location::new(0.3, 0.0, 0.0, 1.0, Snowball(2));
+        assert!(!a.approx_eq(&more_snow, 1e-9)); // snow must match exactly
+
+        let nan = Location::new(0.0, 0.0, 0.0, f64::NAN, Snowball(1));
+        assert_ne!(nan, nan.clone()); // NaN isn't even equal to itself
+    }
+
+    #[test]
+    fn locations_order_by_density_then_fields() {
+        let locations = field();
+        assert!(locations[1] > locations[2] && locations[2] > locations[0]);
+        let left = Location::new(1.0, 0.0, 0.0, 10.0, Snowball(5));
+        let right = Location::new(2.0, 0.0, 0.0, 10.0, Snowball(5));
+        assert!(left < right); // same density, so x decides
+    }
+
+    #[test]
+    fn snowballs_add_and_sum() {
+        assert_eq!(Snowball(10) + Snowball(5), Snowball(15));
+
+        let mut pile = Snowball(10);
+        pile += Snowball(5);
+        assert_eq!(pile, Snowball(15));
+
+        assert_eq!([Snowball(1), Snowball(2), Snowball(3)].into_iter().sum::<Snowball>(), Snowball(6));
+        assert_eq!(std::iter::empty::<Snowball>().sum::<Snowball>(), Snowball(0));
+    }
+
+    #[test]
+    fn kg_and_lb_convert_directly() {
+        assert!((SnowLb::from(SnowKg(1.0)).0 - 2.2046).abs() < 1e-3); // about 2.2046 lb per kg
+        assert!((SnowLb::from(SnowKg(2.0)).0 - 4.41).abs() < 1e-9);
+        assert!((SnowKg::from(SnowLb(4.41)).0 - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn builder_matches_new_and_defaults_to_zero() {
+        let built = Location::builder().x(1.0).y(2.0).z(3.0).area(100.0).snow(SnowKg(5.0)).build();
+        assert_eq!(built, Location::new(1.0, 2.0, 3.0, 100.0, Snowball(25)));
+
+        let bare = Location::builder().z(3.0).build();
+        assert_eq!(bare, Location::new(0.0, 0.0, 3.0, 0.0, Snowball(0)));
+    }
+
+    #[test]
+    fn export_writes_snow_as_a_plain_number() {
+        let json = export_locations(&field());
+        assert_eq!(json[0]["snow"], serde_json::json!(25));
+        assert!(json[0]["snow"].is_i64()); // not {"0": 25} or [25]
+        assert_eq!(
+            json[1],
+            serde_json::json!({ "x": 4.0, "y": 5.0, "z": 6.0, "area": 50.0, "snow": 25 })
+        );
+        assert_eq!(json.as_array().map(Vec::len), Some(3));
+    }
+
+    #[test]
+    fn export_writes_nan_as_null() {
+        let json = export_locations(&[Location::new(0.0, 0.0, 0.0, f64::NAN, Snowball(1))]);
+        assert!(json[0]["area"].is_null());
+    }
+
+    #[test]
+    fn value_returns_the_wrapped_number() {
+        assert_eq!(SnowKg(1.5).value(), 1.5);
+        assert_eq!(SnowLb(2.0).value(), 2.0);
+        assert_eq!(Snowball(25).value(), 25);
+    }
+
+    #[test]
+    fn sum_values_adds_up_either_unit() {
+        assert_eq!(sum_values(&[SnowKg(1.5), SnowKg(2.5)]), 4.0);
+        assert_eq!(sum_values(&[SnowLb(1.0), SnowLb(0.5)]), 1.5);
+        assert_eq!(sum_values::<SnowKg>(&[]), 0.0);
+    }
+
+    #[test]
+    fn try_new_rejects_negative_counts() {
+        assert_eq!(Snowball::try_new(3), Ok(Snowball(3)));
+        assert_eq!(Snowball::try_new(0), Ok(Snowball(0)));
+        assert_eq!(Snowball::try_new(-3), Err(SnowError::Negative(-3)));
+        assert_eq!(
+            SnowError::Negative(-3).to_string(),
+            "snowball count can't be negative (got -3)"
+        );
+    }
+
+    #[test]
+    fn negative_weights_clamp_to_zero_snowballs() {
+        assert_eq!(Snowball::from(SnowKg(-1.0)), Snowball(0));
+        assert_eq!(Snowball::from(SnowLb(-1.0)), Snowball(0));
+    }
+
+    #[test]
+    fn with_snow_changes_the_copy_only() {
+        let locations = field();
+        let deeper = locations[0].with_snow(SnowKg(3.0));
+        assert_eq!(deeper.snow, Snowball::from(SnowKg(3.0)));
+        assert_ne!(locations[0].snow, deeper.snow);
+        assert_eq!(locations[0].snow, Snowball(25));
+    }
+
+    #[test]
+    fn sorted_by_density_is_best_first() {
+        // Densities worked by hand (snowballs / area):
+        //   [0] 25 / 100 = 0.25   [1] 25 / 50 = 0.50   [2] 25 / 75 = 0.33
+        let locations = field();
+        let order: Vec<f64> = sorted_by_density(&locations).iter().map(|location| location.area).collect();
+        assert_eq!(order, [50.0, 75.0, 100.0]); // [1], [2], [0]
+    }
+
+    #[test]
+    fn sorted_by_density_puts_a_nan_area_last() {
+        let with_nan = [Location::new(0.0, 0.0, 0.0, f64::NAN, Snowball(1)), field()[0].clone()];
+        assert_eq!(with_nan[0].density(), 0.0);
+        assert!(sorted_by_density(&with_nan)[1].area.is_nan()); // NaN area: density 0.0, so last
+    }
+
+    #[test]
+    fn safe_ratio_guards_zero_and_non_finite_denominators() {
+        assert_eq!(safe_ratio(10.0, 4.0), 2.5);
+        assert_eq!(safe_ratio(10.0, 0.0), 0.0);
+        assert_eq!(safe_ratio(10.0, f64::NAN), 0.0);
+        assert_eq!(safe_ratio(10.0, f64::INFINITY), 0.0);
+    }
+
+    #[test]
+    fn snow_per_unit_volume() {
+        assert_eq!(field()[0].snow_per_unit_volume(), 25.0 / 6.0); // 1 * 2 * 3 = 6
+        assert_eq!(Location::new(1.0, 2.0, 0.0, 10.0, Snowball(5)).snow_per_unit_volume(), 0.0);
+    }
+
+    #[test]
+    fn best_location_is_found_by_index() {
+        let locations = field();
+        let index = find_best_location_index(&locations).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(find_best_location(locations).unwrap().area, 50.0);
+        assert!(find_best_location_index(&[]).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+// ============================================================================
+// error: One error type for the io_harness functions
+// ============================================================================
+//
+// io::Error says "something went wrong with I/O". Bad UTF-8 is a different
+// kind of problem - the bytes arrived fine, they just aren't text - so it gets
+// its own variant instead of hiding inside io::ErrorKind::InvalidData.
+
+use std::io;
+use thiserror::Error;
+// ↑            ↑
+// │            └─ #[derive(Error)] writes the Display and Error impls for us
+// └────────────── The same crate the weather dashboard uses for WeatherError
+
+/// Everything an `io_harness` function can fail with
+///
+/// `?` turns an `io::Error` into `StdioError::Io` automatically, and a
+/// `StdioError` back into an `io::Error`, so examples returning
+/// `io::Result` can keep using `?` on these functions:
+/// ```
+/// use stdio_learning::error::StdioError;
+/// use stdio_learning::io_harness::read_all_lines;
+///
+/// let err = read_all_lines(&b"ok\nbad \xff byte\n"[..]).unwrap_err();
+/// assert!(matches!(err, StdioError::InvalidUtf8 { line: 2 }));
+/// assert_eq!(err.to_string(), "line 2 is not valid UTF-8");
+///
+/// let err: std::io::Error = err.into();
+/// assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+/// ```
+#[derive(Debug, Error)]
+pub enum StdioError {
+    /// Reading or writing itself failed (closed pipe, full disk, ...)
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    //  ↑
+    //  └─ #[from] generates From<io::Error>, which is what `?` calls
+
+    /// A line's bytes aren't valid UTF-8 (1-based line number)
+    #[error("line {line} is not valid UTF-8")]
+    InvalidUtf8 { line: usize },
+}
+
+impl From<StdioError> for io::Error {
+    fn from(err: StdioError) -> io::Error {
+        match err {
+            StdioError::Io(e) => e,
+            // ↑ Hand back the original - its kind (e.g. BrokenPipe) still matters to callers
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
@@ -6,13 +6,15 @@
 //   cargo run --example ex01_basic_stdio
 //   echo "Alice" | cargo run --example ex01_basic_stdio
 //   cargo run --example ex01_basic_stdio < input.txt
+//   cargo test --example ex01_basic_stdio
 
 // ----------------------------------------------------------------------------
 // IMPORTS: Bringing functionality into scope
 // ----------------------------------------------------------------------------
-use std::io::{self, Write};
-// ↑   ↑   ↑   ↑      ↑      ↑
-// │   │   │   │      │      └─ Import the Write trait (adds .flush() method)
+use std::io::{self, BufRead, Write};
+// ↑   ↑   ↑   ↑      ↑        ↑      ↑
+// │   │   │   │      │        │      └─ Import the Write trait (adds .flush() method)
+// │   │   │   │      │        └──────── Import the BufRead trait (adds .read_line() to any reader)
 // │   │   │   │      └──────── Import the io module itself (lets us use io::stdin())
 // │   │   │   └─────────────── Curly braces {} for multiple imports from same module
 // │   │   └─────────────────── Path separator :: means "inside of"
@@ -22,6 +24,7 @@ use std::io::{self, Write};
 // Why do we need this?
 // - `self` lets us write io::stdin() instead of std::io::stdin()
 // - `Write` is a trait that adds the .flush() method to stdout/stderr
+// - `BufRead` is a trait that lets read_name() accept stdin OR any other buffered input
 
 // ----------------------------------------------------------------------------
 // MAIN FUNCTION: Entry point of the program
@@ -88,77 +91,25 @@ fn main() {
     // PART 3: Reading from stdin (Standard Input Stream)
     // ========================================================================
 
-    let mut input = String::new();
-    // ↑   ↑   ↑       ↑      ↑
-    // │   │   │       │      └─── () calls the function
-    // │   │   │       └────────── new() is an "associated function" (like static method)
-    // │   │   │                   Creates a new, empty String
-    // │   │   └────────────────── String = growable text type (can change size)
-    // │   └────────────────────── Variable name (we chose this name)
-    // └────────────────────────── "let" declares a new variable
+    match read_name(io::stdin().lock()) {
+    // ↑     ↑         ↑       ↑
+    // │     │         │       └──── .lock() gives read_name a buffered (BufRead) handle
+    // │     │         └──────────── stdin() returns handle to standard input
+    // │     └────────────────────── Our function below - does the actual reading
+    // └──────────────────────────── "match" = pattern matching (like switch, but powerful)
     //
-    // "mut" = mutable (can be changed after creation)
-    // Without "mut", variables are immutable (can't change)
-    // We need "mut" because read_line() will modify this string
+    // read_name returns io::Result<Option<String>>, so there are THREE outcomes:
+    //   - Ok(Some(name)) → we got a name
+    //   - Ok(None)       → reading worked, but the line was empty
+    //   - Err(error)     → reading itself failed
+    // "match" forces you to handle all of them!
 
-    match io::stdin().read_line(&mut input) {
-    // ↑     ↑  ↑       ↑          ↑    ↑
-    // │     │  │       │          │    └──── The variable to read into
-    // │     │  │       │          └───────── &mut = mutable reference (borrow it, can modify)
-    // │     │  │       └──────────────────── read_line() reads until user presses Enter
-    // │     │  │                             Returns Result<usize, Error>
-    // │     │  └──────────────────────────── () calls the function
-    // │     └─────────────────────────────── stdin() returns handle to standard input
-    // └───────────────────────────────────── "match" = pattern matching (like switch, but powerful)
-    //
-    // "match" forces you to handle all possible outcomes:
-    //   - Success case: Ok(bytes_read)
-    //   - Error case: Err(error)
-
-        Ok(bytes_read) => {
-        // ↑  ↑           ↑
-        // │  │           └─ => means "if this pattern matches, do this"
-        // │  └───────────── Variable name - captures the number of bytes read
-        // └──────────────── Pattern: if read_line succeeded, it returns Ok(number)
-
-            eprintln!("[Debug] Read {} bytes from stdin", bytes_read);
-            //                       ↑                    ↑
-            //                       │                    └─ Value to insert into {}
-            //                       └────────────────────── {} is a placeholder
-            //
-            // Example output: "[Debug] Read 6 bytes from stdin"
-            // (5 characters for "Alice" + 1 for newline \n)
-
-            let name = input.trim();
-            //         ↑     ↑
-            //         │     └──── .trim() removes whitespace from start and end
-            //         │           Removes spaces, tabs, newlines (\n)
-            //         └────────── The variable we read into above
-            //
-            // User typed "Alice\n" (Enter adds \n)
-            // trim() returns "Alice"
-
-            if name.is_empty() {
-            // ↑  ↑    ↑
-            // │  │    └───────── () calls the method
-            // │  └────────────── .is_empty() returns true if string has length 0
-            // └───────────────── "if" = conditional statement
-
-                eprintln!("Error: No name provided!");
-                // Write error to stderr
-
-                std::process::exit(1);
-                // ↑   ↑       ↑    ↑
-                // │   │       │    └─ Exit code 1 (non-zero = error)
-                // │   │       │       Code 0 = success, 1+ = error
-                // │   │       └────── exit() function terminates the program
-                // │   └────────────── process module (process control)
-                // └────────────────── std = standard library (full path, since we only imported io)
-                //
-                // The program stops here if name is empty
-            }
-
-            // If we get here, name is not empty - continue...
+        Ok(Some(name)) => {
+        // ↑  ↑    ↑       ↑
+        // │  │    │       └─ => means "if this pattern matches, do this"
+        // │  │    └───────── Variable name - captures the trimmed name
+        // │  └────────────── Some = "there is a value"
+        // └───────────────── Ok = reading succeeded
 
             // ================================================================
             // PART 4: Writing to stdout (Standard Output Stream)
@@ -202,10 +153,29 @@ fn main() {
             // println! includes \n, which triggers flush anyway
         }
 
+        Ok(None) => {
+        // ↑  ↑
+        // │  └─ None = "no value" - the user just pressed Enter (or sent nothing)
+        // └──── Reading still succeeded
+
+            eprintln!("Error: No name provided!");
+            // Write error to stderr
+
+            std::process::exit(1);
+            // ↑   ↑       ↑    ↑
+            // │   │       │    └─ Exit code 1 (non-zero = error)
+            // │   │       │       Code 0 = success, 1+ = error
+            // │   │       └────── exit() function terminates the program
+            // │   └────────────── process module (process control)
+            // └────────────────── std = standard library (full path, since we only imported io)
+            //
+            // main decides to exit - read_name just reports what it found
+        }
+
         Err(error) => {
         // ↑   ↑
         // │   └───────── Variable name - captures the error object
-        // └───────────── Pattern: if read_line failed, it returns Err(error_object)
+        // └───────────── Pattern: if reading failed, read_name returns Err(error_object)
         //
         // This handles errors like:
         // - stdin was closed
@@ -222,6 +192,63 @@ fn main() {
     } // End of match
 } // End of main function
 
+// ----------------------------------------------------------------------------
+// READ_NAME: Read one line and tidy it up - no printing, no exiting
+// ----------------------------------------------------------------------------
+fn read_name<R: BufRead>(mut input: R) -> io::Result<Option<String>> {
+// ↑         ↑           ↑              ↑
+// │         │           │              └─ Ok(None) if the line was blank, Ok(Some(name)) otherwise
+// │         │           └──────────────── Where to read from - we take ownership, so it's `mut`
+// │         └──────────────────────────── R = any type with buffered reading (BufRead)
+// │                                       stdin().lock() in main, b"Alice\n" as &[u8] in a test
+// └────────────────────────────────────── Generic function
+//
+// Because it doesn't touch stdin directly, this works with ANY input source.
+
+    let mut line = String::new();
+    // ↑   ↑   ↑      ↑      ↑
+    // │   │   │      │      └─── () calls the function
+    // │   │   │      └────────── new() is an "associated function" (like static method)
+    // │   │   │                  Creates a new, empty String
+    // │   │   └───────────────── String = growable text type (can change size)
+    // │   └───────────────────── Variable name (we chose this name)
+    // └───────────────────────── "let" declares a new variable
+    //
+    // "mut" = mutable (can be changed after creation)
+    // We need "mut" because read_line() will modify this string
+
+    let bytes_read = input.read_line(&mut line)?;
+    //               ↑     ↑          ↑         ↑
+    //               │     │          │         └─ ? = if reading failed, return the Err to our caller
+    //               │     │          └─────────── &mut = mutable reference (borrow it, can modify)
+    //               │     └────────────────────── read_line() reads until newline (user presses Enter)
+    //               └──────────────────────────── The input we were given
+    //
+    // Returns how many bytes were read (0 means EOF - nothing at all)
+
+    eprintln!("[Debug] Read {} bytes from stdin", bytes_read);
+    // Example output: "[Debug] Read 6 bytes from stdin"
+    // (5 characters for "Alice" + 1 for newline \n)
+
+    let name = line.trim();
+    //         ↑    ↑
+    //         │    └──── .trim() removes whitespace from start and end
+    //         │          Removes spaces, tabs, newlines (\n)
+    //         └───────── The line we read into above
+    //
+    // User typed "Alice\n" (Enter adds \n)
+    // trim() returns "Alice"
+
+    if name.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(name.to_string()))
+        //           ↑
+        //           └─ trim() returned a &str borrowed from `line`, which is about
+        //              to go away - .to_string() makes an owned copy to return
+    }
+}
+
 // ============================================================================
 // KEY CONCEPTS SUMMARY:
 // ============================================================================
@@ -250,3 +277,47 @@ fn main() {
 // - stdout is LINE-BUFFERED (flushes on \n or manual flush)
 // - stderr is UNBUFFERED (appears immediately)
 // - .flush() forces buffer to write now
+
+// ============================================================================
+// TESTS: read_name with canned input instead of a keyboard
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    // ↑ Cursor wraps bytes in memory and implements BufRead, just like stdin().lock()
+
+    #[test]
+    fn a_name_is_trimmed() {
+        assert_eq!(read_name(Cursor::new("Alice\n")).unwrap(), Some("Alice".to_string()));
+        assert_eq!(read_name(Cursor::new("  Alice \r\n")).unwrap(), Some("Alice".to_string()));
+    }
+
+    #[test]
+    fn blank_input_and_eof_are_none() {
+        assert_eq!(read_name(Cursor::new("\n")).unwrap(), None);
+        assert_eq!(read_name(Cursor::new("   \n")).unwrap(), None);
+        assert_eq!(read_name(Cursor::new("")).unwrap(), None); // EOF: 0 bytes read
+    }
+
+    #[test]
+    fn only_the_first_line_is_read() {
+        assert_eq!(read_name(Cursor::new("Alice\nBob\n")).unwrap(), Some("Alice".to_string()));
+        assert_eq!(read_name(Cursor::new("no newline")).unwrap(), Some("no newline".to_string()));
+    }
+
+    #[test]
+    fn cafe_is_four_chars_but_five_bytes() {
+        let name = read_name(Cursor::new("café\n")).unwrap().unwrap();
+        assert_eq!(name, "café");
+        assert_eq!(name.chars().count(), 4);
+        assert_eq!(name.len(), 5);
+        // ↑ len() counts BYTES - é takes two of them in UTF-8
+    }
+
+    #[test]
+    fn invalid_utf8_is_an_error_not_a_panic() {
+        let error = read_name(Cursor::new(b"caf\xe9\n".to_vec())).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
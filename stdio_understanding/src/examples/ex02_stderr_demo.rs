@@ -26,27 +26,44 @@
 //   ↑ Redirects stdout to all.txt, then redirects stderr to wherever stdout goes
 //   ↑ "2>&1" means "make fd 2 point to the same place as fd 1"
 //   ↑ Everything ends up in all.txt
+//
+//   cargo test --example ex02_stderr_demo
+//   ↑ Runs the demo into memory buffers and verifies every STDOUT: line went
+//   ↑ to stdout and every STDERR: line to stderr
 
 // ----------------------------------------------------------------------------
-// NO IMPORTS NEEDED
+// IMPORTS
 // ----------------------------------------------------------------------------
-// This example uses only println! and eprintln! which are built-in macros
-// They're always available without any "use" statements
-
-fn main() {
-// No parameters, nothing fancy - just demonstrating output separation
+use std::io::{self, Write};
+// ↑   ↑   ↑    ↑     ↑
+// │   │   │    │     └─ Write trait (writeln! on any writer, not just stdout)
+// │   │   │    └─────── Import io module itself (io::Result, io::stdout)
+// │   │   └──────────── Multiple imports from io
+// │   └──────────────── Path separator
+// └──────────────────── Standard library
+
+pub fn run<O: Write, E: Write>(out: &mut O, err: &mut E) -> io::Result<()> {
+// ↑      ↑           ↑          ↑              ↑
+// │      │           │          │              └─ Where diagnostics go (stderr in main)
+// │      │           │          └──────────────── Where data goes (stdout in main)
+// │      │           └─────────────────────────── E = any writer for the diagnostics
+// │      └─────────────────────────────────────── O = any writer for the data
+// └────────────────────────────────────────────── pub so a test can call it with buffers
+//
+// writeln!(out, ...) is println!, writeln!(err, ...) is eprintln! - but the
+// caller picks the destinations. See the test at the bottom.
 
     // ========================================================================
     // THE PATTERN: Alternating stdout and stderr
     // ========================================================================
     // We label each line so you can see where it goes when you redirect
 
-    println!("STDOUT: This is regular output");
-    // ↑ println! goes to stdout (fd 1)
+    writeln!(out, "STDOUT: This is regular output")?;
+    // ↑ out is stdout (fd 1) when main calls us - like println!
     // This is "data" - the main output of the program
 
-    eprintln!("STDERR: This is error/diagnostic output");
-    // ↑ eprintln! goes to stderr (fd 2)
+    writeln!(err, "STDERR: This is error/diagnostic output")?;
+    // ↑ err is stderr (fd 2) when main calls us - like eprintln!
     // This is diagnostics - information ABOUT the process, not the results
 
     // Try: cargo run --example ex02_stderr_demo > data.txt
@@ -57,10 +74,10 @@ fn main() {
     // REALISTIC EXAMPLE: Processing with progress
     // ========================================================================
 
-    println!("STDOUT: Processing data...");
+    writeln!(out, "STDOUT: Processing data...")?;
     // Main output: telling the user we're working
 
-    eprintln!("STDERR: [Debug] Starting processing");
+    writeln!(err, "STDERR: [Debug] Starting processing")?;
     // Diagnostic: internal state for debugging
     // Marked with [Debug] to show it's not part of the result
 
@@ -80,14 +97,15 @@ fn main() {
     // "for" loops iterate over ranges, arrays, collections, etc.
     // This will run 3 times: i=1, then i=2, then i=3
 
-        println!("STDOUT: Result #{}: Data processed", i);
-        //                      ↑                     ↑
-        //                      └─ First placeholder  └─ Value to insert
+        writeln!(out, "STDOUT: Result #{}: Data processed", i)?;
+        //                             ↑                    ↑
+        //                             │                    └─ Value to insert
+        //                             └─ First placeholder
         //
         // Output goes to stdout (fd 1) - this is DATA
         // Example: "STDOUT: Result #1: Data processed"
 
-        eprintln!("STDERR: [Debug] Processed item {}", i);
+        writeln!(err, "STDERR: [Debug] Processed item {}", i)?;
         // Output goes to stderr (fd 2) - this is DIAGNOSTICS
         // Example: "STDERR: [Debug] Processed item 1"
     }
@@ -97,14 +115,14 @@ fn main() {
     // FINAL OUTPUT: Summary
     // ========================================================================
 
-    println!("STDOUT: === Final Results ===");
-    println!("STDOUT: Total items: 3");
-    println!("STDOUT: Status: Success");
+    writeln!(out, "STDOUT: === Final Results ===")?;
+    writeln!(out, "STDOUT: Total items: 3")?;
+    writeln!(out, "STDOUT: Status: Success")?;
     // These are the "result" - what you'd want if piping to another program
     // Example: program1 | program2
     //   program1's stdout becomes program2's stdin
 
-    eprintln!("STDERR: [Debug] Program completed");
+    writeln!(err, "STDERR: [Debug] Program completed")?;
     // Final diagnostic message - not part of the data stream
 
     // ========================================================================
@@ -136,7 +154,16 @@ fn main() {
     //    "Write programs that do one thing well and work together"
     //    Separate streams make programs composable building blocks
 
-} // End of main
+    Ok(())
+} // End of run
+
+fn main() -> io::Result<()> {
+    run(&mut io::stdout().lock(), &mut io::stderr().lock())
+    //  ↑                         ↑
+    //  │                         └─ The real fd 2
+    //  └─────────────────────────── The real fd 1
+}
+
 
 // ============================================================================
 // EXPERIMENTS TO TRY:
@@ -180,3 +207,29 @@ fn main() {
 // >>    = Append to file instead of overwriting
 // |     = Pipe stdout to next command (stderr not piped!)
 // |&    = Pipe both stdout and stderr (bash shorthand for 2>&1 |)
+
+// ============================================================================
+// TEST: Did every line land on the right stream?
+// ============================================================================
+// Swapping one writeln!(out, ...) for writeln!(err, ...) is an easy slip that
+// still compiles and still "looks" fine on a terminal. This catches it:
+// every line run() writes is labelled, so the label must match the stream.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stdio_learning::io_harness::capture;
+    // Runs run() against in-memory buffers - see src/io_harness.rs
+
+    #[test]
+    fn every_line_lands_on_its_labelled_stream() {
+        let (out, err) = capture(run).unwrap();
+        assert!(!out.is_empty() && !err.is_empty());
+
+        for line in out.lines() {
+            assert!(line.starts_with("STDOUT:"), "on stdout: {:?}", line);
+        }
+        for line in err.lines() {
+            assert!(line.starts_with("STDERR:"), "on stderr: {:?}", line);
+        }
+    }
+}
@@ -43,6 +43,9 @@ use std::time::Duration;
 //
 // Duration is used to specify how long to sleep
 
+use stdio_learning::io_harness::progress_bar;
+// Draws one "\r[####------]  40%" frame - see src/io_harness.rs
+
 fn main() {
 
     eprintln!("=== Buffering Demonstration ===\n");
@@ -147,24 +150,22 @@ fn main() {
     // Second part appears after the wait
 
     // ========================================================================
-    // TODO(human): YOUR TURN TO EXPERIMENT!
+    // PART 4: A progress bar that updates IN PLACE
     // ========================================================================
     //
-    // Add code here to demonstrate the difference between buffered and
-    // unbuffered output. Create a function that writes to stdout without
-    // newlines in a loop, comparing behavior with and without manual flushing.
-    //
-    // HINT: Try a progress bar simulation that updates in place using \r
-    //       (carriage return - moves cursor back to start of line)
-    //
-    // Example idea:
-    // for i in 0..=100 {
-    //     print!("\rProgress: {}%", i);  // \r moves cursor to line start
-    //     io::stdout().flush().unwrap();
-    //     thread::sleep(Duration::from_millis(50));
-    // }
-    //
-    // EXPERIMENT: What happens if you don't flush?
+    // \r (carriage return) moves the cursor back to the start of the line,
+    // so each frame overwrites the last one instead of printing a new line.
+    // There's no \n, so nothing would appear without the manual flush!
+
+    eprintln!("\n=== Part 4: Progress Bar ===");
+    let mut stdout = io::stdout();
+    for percent in 0..=100 {
+        progress_bar(&mut stdout, percent).unwrap();
+        thread::sleep(Duration::from_millis(20));
+    }
+    println!(); // Finish the line so the next output starts fresh
+
+    // EXPERIMENT: Remove the flush() inside progress_bar (src/io_harness.rs) - what happens?
     // EXPERIMENT: What happens if you redirect to a file?
     //             cargo run --example ex03_buffering > output.txt
     //             (stdout becomes FULLY buffered when going to a file!)
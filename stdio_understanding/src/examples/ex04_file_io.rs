@@ -18,28 +18,72 @@
 //
 // Try running:
 //   cargo run --example ex04_file_io
+//
+//   cargo run --example ex04_file_io -- --append
+//   ↑ Also APPENDS three lines to append_output.txt - run it a few times
+//   ↑ and watch the file grow (cat append_output.txt)
 
 // ----------------------------------------------------------------------------
 // IMPORTS
 // ----------------------------------------------------------------------------
-use std::fs::File;
-// ↑   ↑   ↑
-// │   │   └─ File type (represents an open file)
-// │   └───── fs = filesystem module
-// └─────────  Standard library
-
-use std::io::{self, Read, Write};
-// ↑   ↑   ↑    ↑     ↑     ↑
-// │   │   │    │     │     └─ Write trait (adds write methods)
-// │   │   │    │     └─────── Read trait (adds read methods)
-// │   │   │    └───────────── Import io module itself
-// │   │   └────────────────── Multiple imports from io
-// │   └────────────────────── Path separator
-// └────────────────────────── Standard library
+use std::fs::{File, OpenOptions};
+// ↑   ↑    ↑     ↑
+// │   │    │     └─ OpenOptions = pick the open mode yourself (append, create, ...)
+// │   │    └─────── File type (represents an open file)
+// │   └──────────── fs = filesystem module
+// └────────────────  Standard library
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+// ↑   ↑   ↑    ↑     ↑     ↑     ↑         ↑
+// │   │   │    │     │     │     │         └─ Write trait (adds write methods)
+// │   │   │    │     │     │     └─────────── SeekFrom = where to seek from (start, end, current)
+// │   │   │    │     │     └───────────────── Seek trait (adds seek() - move the position)
+// │   │   │    │     └─────────────────────── Read trait (adds read methods)
+// │   │   │    └───────────────────────────── Import io module itself
+// │   │   └────────────────────────────────── Multiple imports from io
+// │   └────────────────────────────────────── Path separator
+// └────────────────────────────────────────── Standard library
 //
 // Read and Write are TRAITS - they add methods to types
 // File implements both Read and Write
 
+const LINES: [&str; 3] = ["This is line 1", "This is line 2", "This is line 3"];
+
+// ----------------------------------------------------------------------------
+// WRITE_LINES: One line per entry, to ANY writer
+// ----------------------------------------------------------------------------
+fn write_lines<W: Write>(w: &mut W, lines: &[&str]) -> io::Result<()> {
+// ↑              ↑         ↑            ↑
+// │              │         │            └─ The lines to write (no \n needed)
+// │              │         └────────────── Borrowed mutably - writing changes it
+// │              └──────────────────────── W = a File, stdout, a Vec<u8>...
+// └─────────────────────────────────────── Generic, so a Vec<u8> can stand in for a file
+    for line in lines {
+        writeln!(w, "{}", line)?;
+    }
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// READ_FROM_OFFSET: Jump to a byte position, read `len` bytes from there
+// ----------------------------------------------------------------------------
+fn read_from_offset<F: Read + Seek>(f: &mut F, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+// ↑                   ↑                        ↑            ↑
+// │                   │                        │            └─ How many bytes to read
+// │                   │                        └────────────── Byte position, counted from the start
+// │                   └─────────────────────────────────────── Anything readable AND seekable:
+// │                                                             a File, or a Cursor over bytes in memory
+// └─────────────────────────────────────────────────────────── Returns raw bytes - the middle of a file
+//                                                               may not be valid UTF-8
+    f.seek(SeekFrom::Start(offset))?;
+    // ↑ Moves the position. Nothing is read yet - the next read starts HERE
+
+    let mut buf = vec![0; len];
+    f.read_exact(&mut buf)?;
+    // ↑ Fill buf completely, or fail with UnexpectedEof if the data runs out
+    Ok(buf)
+}
+
 // ----------------------------------------------------------------------------
 // MAIN FUNCTION with ERROR HANDLING
 // ----------------------------------------------------------------------------
@@ -97,21 +141,17 @@ fn main() -> io::Result<()> {
         //     Err(e) => return Err(e),
         // }
 
-        writeln!(file, "This is line 1")?;
-        // ↑        ↑     ↑                ↑
-        // │        │     │                └─ ? operator: if write fails, return error
-        // │        │     └──────────────────  String to write
-        // │        └────────────────────────  The file to write to
-        // └─────────────────────────────────  writeln! macro (like println! but to a file)
+        write_lines(&mut file, &LINES)?;
+        // ↑           ↑          ↑      ↑
+        // │           │          │      └─ ? operator: if a write fails, return error
+        // │           │          └──────── The three lines to write
+        // │           └─────────────────── The file to write to
+        // └─────────────────────────────── Our helper: writeln!(w, ...) for each line
         //
         // writeln! works with ANYTHING that implements the Write trait
-        // File implements Write, so we can use writeln!(file, ...)
+        // File implements Write, so write_lines can take a File
         // Compare: println! always writes to stdout
 
-        writeln!(file, "This is line 2")?;
-        writeln!(file, "This is line 3")?;
-        // Write two more lines
-
         // IMPORTANT: file is automatically closed here at }
         // This is RAII (Resource Acquisition Is Initialization)
         // When `file` goes out of scope, Rust calls its destructor
@@ -175,6 +215,60 @@ fn main() -> io::Result<()> {
     }
     // File is automatically closed here
 
+    // ========================================================================
+    // PART 3: SEEKING (random access - jump into the middle)
+    // ========================================================================
+
+    {
+        let mut file = File::open(filename)?;
+        // A fresh open: the file's position starts at byte 0
+
+        let bytes = read_from_offset(&mut file, 7, 7)?;
+        //                                      ↑  ↑
+        //                                      │  └─ Read 7 bytes...
+        //                                      └──── ...starting at byte 7
+        //
+        //   This is line 1
+        //   0123456789...
+        //          ↑
+        //          └─ byte 7 = the space before "line 1"
+
+        eprintln!("\nBytes 7..14 of '{}': {:?}", filename, String::from_utf8_lossy(&bytes));
+
+        let position = file.stream_position()?;
+        eprintln!("Position is now {} - the file remembers where we stopped", position);
+        // ↑ 14 = 7 + 7. The OS keeps this position for every open file (file descriptor).
+        // read_to_string in PART 2 simply started at 0 and ran to the end.
+        // (The same function works on a Cursor too - see the tests at the bottom.)
+    }
+
+    // ========================================================================
+    // PART 4: APPENDING vs TRUNCATING (only with --append)
+    // ========================================================================
+
+    if std::env::args().any(|arg| arg == "--append") {
+        let append_name = "append_output.txt";
+
+        let mut file = OpenOptions::new().append(true).create(true).open(append_name)?;
+        //             ↑                  ↑            ↑            ↑
+        //             │                  │            │            └─ Open it (returns io::Result<File>)
+        //             │                  │            └────────────── Create the file if it's missing...
+        //             │                  └─────────────────────────── ...otherwise every write goes to the END
+        //             └────────────────────────────────────────────── Builder: start with everything off
+        //
+        // File::create is OpenOptions with write + create + TRUNCATE:
+        // it empties the file first. append(true) keeps what's there.
+
+        write_lines(&mut file, &LINES)?;
+        drop(file);
+        // ↑ Close it now (RAII, just earlier than the end of the block)
+
+        let total = std::fs::read_to_string(append_name)?.lines().count();
+        eprintln!("\nAppended 3 lines to '{}' - it now has {} lines", append_name, total);
+        eprintln!("Run again and it grows by 3 more; File::create would reset it to 3");
+        // (The tests at the bottom show the same difference with a Vec<u8>.)
+    }
+
     // ========================================================================
     // COMPARISON: stdio vs File I/O
     // ========================================================================
@@ -185,7 +279,7 @@ fn main() -> io::Result<()> {
     eprintln!("  - Inherited from parent process");
     eprintln!("  - File descriptors: 0, 1, 2");
     eprintln!("  - Can be redirected: program < in.txt > out.txt");
-    eprintln!("");
+    eprintln!();
     eprintln!("File I/O (File::open/create):");
     eprintln!("  - Explicitly opened by your code");
     eprintln!("  - Gets new file descriptor (3+)");
@@ -324,3 +418,41 @@ fn main() -> io::Result<()> {
 //   let file = File::open(filename)?;
 //
 // Much cleaner! Rust forces you to handle errors, but makes it ergonomic.
+
+// ============================================================================
+// TESTS: Seeking and append-vs-truncate without touching the disk
+// ============================================================================
+// Run with: cargo test --example ex04_file_io
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_from_offset_reads_the_middle_and_leaves_the_position_after_it() {
+        // Cursor adds a position to bytes in memory - Read + Seek, like a File
+        let mut cursor = io::Cursor::new(b"Hello, world!".to_vec());
+        assert_eq!(read_from_offset(&mut cursor, 7, 5).unwrap(), b"world");
+        assert_eq!(cursor.stream_position().unwrap(), 12);
+
+        let mut lines = io::Cursor::new(LINES.join("\n").into_bytes());
+        assert_eq!(read_from_offset(&mut lines, 7, 7).unwrap(), b" line 1");
+        assert_eq!(lines.stream_position().unwrap(), 14);
+    }
+
+    #[test]
+    fn appending_keeps_the_old_lines_and_truncating_drops_them() {
+        let mut appended = Vec::new();
+        write_lines(&mut appended, &LINES).unwrap();
+        write_lines(&mut appended, &LINES).unwrap();
+        // ↑ Appending: the second write lands after the first → 6 lines
+
+        let mut truncated = Vec::new();
+        write_lines(&mut truncated, &LINES).unwrap();
+        truncated.clear();
+        write_lines(&mut truncated, &LINES).unwrap();
+        // ↑ Truncating: empty it first (what File::create does) → 3 lines
+
+        assert_eq!(String::from_utf8_lossy(&appended).lines().count(), 6);
+        assert_eq!(String::from_utf8_lossy(&truncated).lines().count(), 3);
+    }
+}
@@ -29,23 +29,281 @@
 //   seq 1 10 | cargo run --example ex05_pipes | head -3
 //   ↑ seq generates numbers 1-10, we process them, head shows first 3 lines
 //   ↑ This is a 3-program pipeline!
+//
+//   cat README.md | cargo run --example ex05_pipes -- --count-only
+//   ↑ Like `wc`: skip the per-line output, print just "lines words chars"
+//   ↑ (COUNT_ONLY=1 in the environment does the same)
+//
+//   printf 'caf\xc3\xa9\nbad \xff byte\n' | cargo run --example ex05_pipes -- --lossy
+//   ↑ Invalid UTF-8 becomes � instead of stopping the program
+//   ↑ (without --lossy, the \xff line is an error and we exit with code 1)
+//
+//   seq 1 1000000 | cargo run --release --example ex05_pipes -- --fast > /dev/null
+//   ↑ Same output through process_fast(): one reused buffer, no String per line
+//
+//   printf 'hello world\nfoo\n' | cargo run --example ex05_pipes -- --summary-only
+//   ↑ Prints "      2       3      16" - the same line `wc` prints for that input
+//
+//   seq 1 100000 | cargo run --example ex05_pipes | head -3
+//   ↑ head quits after 3 lines; our next write fails with "Broken pipe" and
+//   ↑ we stop quietly with exit code 0 - the reader has everything it wanted
+//
+//   cargo test --example ex05_pipes
+//   ↑ Runs both paths on the same input and verifies the output is byte-for-byte
+//   ↑ identical, and that the wc summary matches real wc
+//   ↑ - prove the fast path before you trust it
 
 // ----------------------------------------------------------------------------
 // IMPORTS
 // ----------------------------------------------------------------------------
-use std::io::{self, BufRead};
-// ↑   ↑   ↑    ↑     ↑
-// │   │   │    │     └─ BufRead trait (adds lines() method for reading line-by-line)
-// │   │   │    └─────── Import io module itself
-// │   │   └──────────── Multiple imports from io
-// │   └──────────────── Path separator
-// └──────────────────── Standard library
+use std::io::{self, BufRead, Read, Write};
+// ↑   ↑   ↑    ↑     ↑        ↑     ↑
+// │   │   │    │     │        │     └─ Write trait (lets run() write to any output, not just stdout)
+// │   │   │    │     │        └─────── Read trait (CountingReader implements it)
+// │   │   │    │     └──────────────── BufRead trait (buffered readers - what run() accepts as input)
+// │   │   │    └──────────────── Import io module itself
+// │   │   └───────────────────── Multiple imports from io
+// │   └───────────────────────── Path separator
+// └───────────────────────────── Standard library
 //
 // BufRead is a trait that adds buffered reading methods
 // stdin() returns something that implements BufRead
 
-fn main() {
+use stdio_learning::io_harness::{read_all_lines, read_all_lines_lossy, with_locked_stdout};
+// ↑                ↑            ↑
+// │                │            └─ Shared helpers - see src/io_harness.rs
+// │                └────────────── Module in this package's library (src/lib.rs)
+// └─────────────────────────────── The package's own library crate
+
+// ----------------------------------------------------------------------------
+// STATS: What run() reports back once the input is exhausted
+// ----------------------------------------------------------------------------
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub lines: usize,
+    pub words: usize,
+    pub bytes: usize, // UTF-8 bytes, not counting line endings
+    pub chars: usize, // Unicode characters - "café" is 4 chars but 5 bytes!
+    pub input_bytes: usize, // EVERY byte read, line endings included - what `wc -c` counts
+}
+
+// ----------------------------------------------------------------------------
+// COUNTINGREADER: Pass reads through, counting the bytes on the way
+// ----------------------------------------------------------------------------
+// read_all_lines() strips the \n and \r\n, so afterwards we can't tell how
+// many bytes there were. Wrapping the reader counts them as they're consumed.
+struct CountingReader<R> {
+    inner: R,
+    count: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+        // ↑ Just a peek at the buffer - nothing is used up yet
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.count += amt;
+        // ↑ lines() and read_until() call this for every byte they take
+        self.inner.consume(amt);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// FORMAT_WC: The summary line `wc` prints - lines, words, bytes, [filename]
+// ----------------------------------------------------------------------------
+pub fn format_wc(stats: &Stats, name: Option<&str>) -> String {
+// ↑                               ↑
+// │                               └─ None for stdin - wc prints no name then either
+// └───────────────────────────────── "      2       3      16" (7-wide columns, like GNU wc on stdin)
+//
+// One difference: wc -l counts \n characters, so a last line WITHOUT a
+// trailing newline isn't counted by wc - but it is a line to us.
+    let counts = format!("{:>7} {:>7} {:>7}", stats.lines, stats.words, stats.input_bytes);
+    match name {
+        Some(name) => format!("{} {}", counts, name),
+        None => counts,
+    }
+}
+
+// ----------------------------------------------------------------------------
+// PROCESS ONE LINE: The actual "business logic", with no I/O at all
+// ----------------------------------------------------------------------------
+pub fn process_line(line: &str) -> (String, usize) {
+// ↑                  ↑             ↑       ↑
+// │                  │             │       └─ Number of words in the line
+// │                  │             └───────── The transformed (uppercased) line
+// │                  └─────────────────────── Borrowed line text (without \n)
+// └────────────────────────────────────────── pub so other code (and tests) can call it
+//
+// No stdin, no stdout - just a string in, a string out.
+// That's what makes it easy to test!
+
+    let words = line.split_whitespace().count();
+    // ↑   ↑     ↑    ↑                  ↑
+    // │   │     │    │                  └─ count() counts items in iterator
+    // │   │     │    └──────────────────── split_whitespace() splits on spaces/tabs/newlines
+    // │   │     │                          Returns iterator over word slices
+    // │   │     └─────────────────────────  The line text
+    // │   └───────────────────────────────  Variable name
+    // └───────────────────────────────────  Declare variable
+    //
+    // Example: "hello  world\t!" → ["hello", "world", "!"] → count = 3
+
+    (line.to_uppercase(), words)
+    // .to_uppercase() returns a new String: "hello" → "HELLO"
+}
+
+// ----------------------------------------------------------------------------
+// RUN: Drive the read → process → write loop over ANY reader and writer
+// ----------------------------------------------------------------------------
+pub fn run<R: BufRead, W: Write>(input: R, mut out: W, lossy: bool) -> io::Result<Stats> {
+// ↑      ↑           ↑          ↑         ↑               ↑             ↑
+// │      │           │          │         │               │             └─ Stats on success, io::Error on failure
+// │      │           │          │         │               └─────────────── Replace bad UTF-8 instead of failing?
+// │      │           │          │         └─────────────────────────────── Where processed lines go
+// │      │           │          └─────────────────────────── Where lines come from
+// │      │           └────────────────────────────────────── W = anything we can write to
+// │      │                                                   (stdout, a File, a Vec<u8>...)
+// │      └────────────────────────────────────────────────── R = anything we can read lines from
+// │                                                          (stdin, a File, a &[u8]...)
+// └───────────────────────────────────────────────────────── Generic function
+//
+// main() passes stdin/stdout. A test can pass b"hello\n" and a Vec<u8> instead -
+// run() doesn't know and doesn't care!
+
+    let mut stats = Stats::default();
+    // Counters for lines processed and total words, both starting at 0
+
+    let mut input = CountingReader { inner: input, count: 0 };
+    let lines = if lossy { read_all_lines_lossy(&mut input)? } else { read_all_lines(&mut input)? };
+    stats.input_bytes = input.count;
+    //                     ↑                          ↑
+    //                     │                          └─ ? = if reading failed, return the error to our
+    //                     │                             caller (main decides what to do about it)
+    //                     └──────────────────────────── Both read until EOF (End Of File):
+    //                                                     - Keyboard: Ctrl+D (Unix) or Ctrl+Z (Windows)
+    //                                                     - File: end of file
+    //                                                     - Pipe: when previous program closes its stdout
+    //
+    // Strict (read_all_lines): a line that isn't valid UTF-8 is an error.
+    // Lossy: bad bytes become � and the line is processed anyway.
+    //
+    // Trade-off: ALL input is read before any output is written. Fine for
+    // this demo - a program streaming huge inputs would loop over lines() instead.
+
+    for text in lines {
+    // ↑   ↑    ↑
+    // │   │    └─ Vec<String> of every line, endings already stripped
+    // │   └────── Variable that holds each line
+    // └────────── for loop - runs once per line of input
+
+        stats.lines += 1;
+        let (upper, words) = process_line(&text);
+        stats.words += words;
+        stats.bytes += text.len();
+        stats.chars += text.chars().count();
+        //             ↑    ↑
+        //             │    └─ .len() on a String counts BYTES, .chars() walks characters
+        //             └────── "é" is one char but two bytes in UTF-8
+
+        // ============================================================
+        // OUTPUT: Data goes to `out` (stdout in main - THIS is what gets piped!)
+        // ============================================================
+
+        writeln!(out, "Line {}: {} (words: {})", stats.lines, upper, words)?;
+        // ↑        ↑    ↑
+        // │        │    └─ Same format string println! would take
+        // │        └────── The writer to send it to
+        // └─────────────── Like println!, but to any Write (and returns a Result)
+        //
+        // Example output: "Line 1: HELLO WORLD (words: 2)"
+
+        // ============================================================
+        // DEBUG: Diagnostics go to stderr (NOT piped!)
+        // ============================================================
+
+        eprintln!("[Debug] Processed line {}", stats.lines);
+        // ↑ Goes to stderr (fd 2)
+        // When piped: program1 | program2
+        //   This appears on your SCREEN, not in program2's stdin!
+    }
+
+    Ok(stats)
+}
+
+// ----------------------------------------------------------------------------
+// PROCESS_FAST: Same output as run(), without a new String for every line
+// ----------------------------------------------------------------------------
+pub fn process_fast<R: BufRead, W: Write>(mut input: R, mut out: W) -> io::Result<Stats> {
+// ↑                                       ↑
+// │                                       └─ mut: read_until advances the reader
+// └───────────────────────────────────────── Strict UTF-8 like run(input, out, false)
+//
+// run() collects a Vec<String> - one heap allocation per line, all kept at once.
+// Here ONE byte buffer and ONE String are reused for every line: after the
+// first few lines they're big enough, and the loop stops allocating at all.
+// (No [Debug] line per input line either - stderr is slow, and this is the fast path.)
+
+    let mut stats = Stats::default();
+    let mut buf = Vec::new();
+    let mut upper = String::new();
+
+    while input.read_until(b'\n', &mut buf)? > 0 {
+    //          ↑          ↑       ↑
+    //          │          │       └─ Appends into buf - its capacity survives clear()
+    //          │          └───────── Stop after the newline
+    //          └──────────────────── Returns bytes read, 0 at EOF
+        stats.input_bytes += buf.len();
+        let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        // Same endings lines() strips
+
+        let text = std::str::from_utf8(line)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // ↑ Borrows buf as &str - no copy. Bad UTF-8 is an InvalidData error, as with lines()
 
+        upper.clear();
+        upper.extend(text.chars().flat_map(char::to_uppercase));
+        // ↑ What text.to_uppercase() returns, written into the reused String
+
+        stats.lines += 1;
+        let words = text.split_whitespace().count();
+        stats.words += words;
+        stats.bytes += text.len();
+        stats.chars += text.chars().count();
+
+        writeln!(out, "Line {}: {} (words: {})", stats.lines, upper, words)?;
+        buf.clear();
+        // ↑ Empty, but keeps its capacity for the next line
+    }
+
+    Ok(stats)
+}
+
+// ----------------------------------------------------------------------------
+// EXIT_CODE: What a finished run() means for the process
+// ----------------------------------------------------------------------------
+fn exit_code<T>(result: &io::Result<T>) -> i32 {
+    match result {
+        Ok(_) => 0,
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => 0,
+        // ↑ The reader hung up (head -3, less then q...). Not our failure -
+        //   it has all the output it wanted. `yes | head -1` exits 0 the same way.
+        Err(_) => 1,
+    }
+}
+
+fn main() {
     // ========================================================================
     // HEADER: Diagnostics to stderr (won't be piped)
     // ========================================================================
@@ -62,164 +320,103 @@ fn main() {
     // This means you see progress/debug info even when piping!
 
     // ========================================================================
-    // SETUP: Get stdin handle and initialize counters
+    // WIRING: Hand stdin and stdout to run()
     // ========================================================================
 
-    let stdin = io::stdin();
-    // ↑   ↑     ↑  ↑
-    // │   │     │  └─ stdin() function returns a handle to standard input
-    // │   │     └──── io module
-    // │   └────────── Variable name
-    // └────────────── Declare immutable variable
+    let summary_only = std::env::args().any(|arg| arg == "--summary-only");
+    // ↑ Like --count-only, but the line is in wc's format: lines words bytes
+
+    let count_only = summary_only
+        || std::env::args().any(|arg| arg == "--count-only")
+        || std::env::var_os("COUNT_ONLY").is_some();
+    // ↑ Set by the --count-only flag or a COUNT_ONLY environment variable
+
+    let lossy = std::env::args().any(|arg| arg == "--lossy");
+    // ↑ Strict by default: bad UTF-8 is an error unless you ask otherwise
+
+    let fast = std::env::args().any(|arg| arg == "--fast");
+    if fast && lossy {
+        eprintln!("--fast is strict UTF-8 only; drop --lossy or --fast");
+        std::process::exit(2);
+    }
+
+    let result = if fast && count_only {
+        process_fast(io::stdin().lock(), io::sink())
+    } else if fast {
+        with_locked_stdout(|out| process_fast(io::stdin().lock(), out))
+    } else if count_only {
+        run(io::stdin().lock(), io::sink(), lossy)
+        //                      ↑
+        //                      └─ io::sink() is a writer that throws everything away
+        //                         (like > /dev/null) - run() doesn't need to know!
+    } else {
+        with_locked_stdout(|out| run(io::stdin().lock(), out, lossy))
+        //                 ↑         ↑                   ↑
+        //                 │         │                   └─ stdout handle (fd 1) - where data goes
+        //                 │         └───────────────────── stdin handle (fd 0), locked for buffered reading
+        //                 └─────────────────────────────── Locked stdout, lent to us for the call
+    };
+
+    let stats = match result {
     //
-    // stdin is a handle we can read from
-    // It could be:
+    // stdin could be:
     //   - Keyboard input (normal terminal)
     //   - File input (< input.txt)
     //   - Pipe input (program1 | program2)
     // Your program doesn't know and doesn't care!
 
-    let mut line_count = 0;
-    // ↑   ↑   ↑         ↑
-    // │   │   │         └─ Initial value
-    // │   │   └─────────── Variable name
-    // │   └─────────────── mut = mutable (we'll increment it)
-    // └─────────────────── Declare variable
-    //
-    // Counter for number of lines processed
+        Ok(stats) => stats,
 
-    let mut word_count = 0;
-    // Counter for total number of words across all lines
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+            std::process::exit(0);
+            // The reader went away - nothing to report, nothing more to write
+            // (see exit_code() above for why 0)
+        }
 
-    // ========================================================================
-    // MAIN LOOP: Read and process lines from stdin
-    // ========================================================================
+        Err(e) => {
+            eprintln!("Error reading line: {}", e);
+            // Error message to stderr
 
-    for line in stdin.lock().lines() {
-    // ↑   ↑    ↑     ↑      ↑
-    // │   │    │     │      └─ lines() returns an iterator over lines
-    // │   │    │     │         Each item is Result<String, Error>
-    // │   │    │     └──────── lock() gets exclusive access to stdin
-    // │   │    │               (needed for efficient buffered reading)
-    // │   │    └────────────── The stdin handle from above
-    // │   └─────────────────── Variable that holds each line
-    // └─────────────────────── for loop - iterate over something
-    //
-    // This loop runs once per line of input
-    // It reads until EOF (End Of File):
-    //   - Keyboard: Ctrl+D (Unix) or Ctrl+Z (Windows)
-    //   - File: end of file
-    //   - Pipe: when previous program closes its stdout
-
-        match line {
-        // ↑     ↑
-        // │     └─ The Result<String, Error> from lines()
-        // └─────── Pattern matching (handle both Ok and Err cases)
-
-            Ok(text) => {
-            // ↑  ↑
-            // │  └──────── Variable name - contains the line text (without \n)
-            // └───────────  Pattern: if reading succeeded
-
-                line_count += 1;
-                // ↑          ↑  ↑
-                // │          │  └─ Increment by 1
-                // │          └──── += operator (add and assign)
-                // └───────────────  The counter variable
-                //
-                // Equivalent to: line_count = line_count + 1;
-
-                let words = text.split_whitespace().count();
-                // ↑   ↑     ↑    ↑                  ↑
-                // │   │     │    │                  └─ count() counts items in iterator
-                // │   │     │    └──────────────────── split_whitespace() splits on spaces/tabs/newlines
-                // │   │     │                          Returns iterator over word slices
-                // │   │     └─────────────────────────  The line text
-                // │   └───────────────────────────────  Variable name
-                // └───────────────────────────────────  Declare variable
-                //
-                // Example: "hello  world\t!" → ["hello", "world", "!"] → count = 3
-
-                word_count += words;
-                // Add this line's word count to total
-
-                // ============================================================
-                // OUTPUT: Data goes to stdout (THIS is what gets piped!)
-                // ============================================================
-
-                println!("Line {}: {} (words: {})", line_count, text.to_uppercase(), words);
-                // ↑        ↑     ↑  ↑  ↑          ↑   ↑          ↑    ↑              ↑
-                // │        │     │  │  │          │   │          │    │              └─ Third value (words)
-                // │        │     │  │  │          │   │          │    └──────────────── .to_uppercase() converts to uppercase
-                // │        │     │  │  │          │   │          │                      Returns new String: "hello" → "HELLO"
-                // │        │     │  │  │          │   │          └───────────────────── The line text
-                // │        │     │  │  │          │   └──────────────────────────────── Second value (text)
-                // │        │     │  │  │          └──────────────────────────────────── First value (line_count)
-                // │        │     │  │  └─────────────────────────────────────────────── Third placeholder
-                // │        │     │  └────────────────────────────────────────────────── Second placeholder
-                // │        │     └───────────────────────────────────────────────────── First placeholder
-                // │        └─────────────────────────────────────────────────────────── Format string
-                // └──────────────────────────────────────────────────────────────────── Macro - writes to STDOUT
-                //
-                // This is the MAIN OUTPUT - goes to stdout (fd 1)
-                // When piped, THIS text goes to the next program!
-                // Example output: "Line 1: HELLO WORLD (words: 2)"
-
-                // ============================================================
-                // DEBUG: Diagnostics go to stderr (NOT piped!)
-                // ============================================================
-
-                eprintln!("[Debug] Processed line {}", line_count);
-                // ↑ Goes to stderr (fd 2)
-                // When piped: program1 | program2
-                //   This appears on your SCREEN, not in program2's stdin!
-                //
-                // This is why stderr is so important for pipes
-                // You can see debug info while data flows through the pipeline
-            }
-
-            Err(e) => {
-            // ↑   ↑
-            // │   └────── Variable name - contains the error object
-            // └────────── Pattern: if reading failed
-
-                eprintln!("Error reading line: {}", e);
-                // Error message to stderr
-
-                std::process::exit(1);
-                // Exit with error code 1
-                // This terminates the program immediately
-            }
-        } // End of match
-    } // End of for loop
-    //
-    // Loop ends when stdin reaches EOF:
-    //   - User pressed Ctrl+D
-    //   - Input file ended
-    //   - Previous program in pipe closed its stdout
+            std::process::exit(1);
+            // Exit with error code 1
+            // This terminates the program immediately
+        }
+    };
 
     // ========================================================================
     // STATISTICS: Summary to stderr (diagnostics, not data)
     // ========================================================================
 
     eprintln!("\n=== Statistics ===");
-    eprintln!("Total lines: {}", line_count);
-    eprintln!("Total words: {}", word_count);
+    eprintln!("Total lines: {}", stats.lines);
+    eprintln!("Total words: {}", stats.words);
+    eprintln!("Total chars: {} ({} bytes)", stats.chars, stats.bytes);
     //
     // All to stderr! These are ABOUT the processing, not the result
     // When piped, these appear on screen, not in the pipe
 
+    let summary = if summary_only {
+        Some(format_wc(&stats, None))
+        // stdin has no filename, so wc-style output has none either
+    } else if count_only {
+        Some(format!("{} {} {}", stats.lines, stats.words, stats.chars))
+        // The ONE line of data in count-only mode - to stdout, so it can be piped
+    } else {
+        None
+    };
+    if let Some(summary) = summary {
+        let written = writeln!(io::stdout().lock(), "{}", summary);
+        // ↑ Not println! - it PANICS if stdout is a closed pipe. writeln! hands us the error
+        if exit_code(&written) != 0 {
+            std::process::exit(1);
+        }
+    }
+
     // ========================================================================
     // HELP MESSAGE: If no input was received
     // ========================================================================
 
-    if line_count == 0 {
-    // ↑  ↑           ↑  ↑
-    // │  │           │  └─ Compare to 0
-    // │  │           └──── == operator (equality check)
-    // │  └────────────────  The counter variable
-    // └───────────────────  if conditional
-
+    if stats.lines == 0 {
         eprintln!("\nNote: No input received. Try:");
         eprintln!("  echo 'hello world' | cargo run --example ex05_pipes");
         // Helpful message to stderr if user ran without input
@@ -360,3 +557,105 @@ fn main() {
 //   - Advanced bash feature: process substitution
 //
 // Most of the time, you want the default: only stdout piped!
+
+// ============================================================================
+// TESTS: Prove the fast path (and the pipe handling) before you trust it
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_line_uppercases_and_counts_words() {
+        assert_eq!(process_line("hello world"), ("HELLO WORLD".to_string(), 2));
+        assert_eq!(process_line("  hello  world\t! "), ("  HELLO  WORLD\t! ".to_string(), 3));
+        assert_eq!(process_line(""), (String::new(), 0));
+    }
+
+    #[test]
+    fn run_writes_numbered_lines_and_returns_stats() {
+        let mut out = Vec::new();
+        let stats = run(&b"hello world\nfoo\n"[..], &mut out, false).unwrap();
+        //              ↑                           ↑
+        //              │                           └─ Vec<u8> stands in for stdout
+        //              └───────────────────────────── &[u8] stands in for stdin
+
+        assert_eq!(String::from_utf8(out).unwrap(), "Line 1: HELLO WORLD (words: 2)\nLine 2: FOO (words: 1)\n");
+        assert_eq!((stats.lines, stats.words), (2, 3));
+    }
+
+    #[test]
+    fn run_on_empty_input_writes_nothing() {
+        let mut out = Vec::new();
+        let stats = run(&b""[..], &mut out, false).unwrap();
+        assert!(out.is_empty());
+        assert_eq!(stats, Stats::default());
+    }
+
+    #[test]
+    fn cafe_is_four_chars_but_five_bytes() {
+        let stats = run("café\r\n".as_bytes(), io::sink(), false).unwrap();
+        assert_eq!(stats.chars, 4);
+        assert_eq!(stats.bytes, 5); // é is 2 bytes in UTF-8
+        assert_eq!(stats.input_bytes, 7); // ...plus the \r\n that bytes leaves out
+    }
+
+    #[test]
+    fn count_only_output_goes_nowhere_but_the_stats_still_add_up() {
+        // What --count-only does: run() into io::sink(), then print the stats
+        let stats = run(&b"hello world\nna\xc3\xafve\n"[..], io::sink(), false).unwrap();
+        assert_eq!(format!("{} {} {}", stats.lines, stats.words, stats.chars), "2 3 16");
+    }
+
+    // Both paths, same input, compare the bytes
+    #[test]
+    fn fast_path_output_is_identical() {
+        let input: &[u8] = b"hello world\n  two   words \r\ncaf\xc3\xa9 \xce\xa3\n\nstra\xc3\x9fe\nno newline at end";
+        //                   ↑               ↑                ↑             ↑   ↑               ↑
+        //                   │               │                │             │   │               └─ Last line without \n
+        //                   │               │                │             │   └───────────────── ß uppercases to SS (2 chars!)
+        //                   │               │                │             └───────────────────── An empty line
+        //                   │               │                └─────────────────────────────────── é and Σ - multi-byte UTF-8
+        //                   │               └──────────────────────────────────────────────────── Windows line ending
+        //                   └──────────────────────────────────────────────────────────────────── Plain ASCII
+
+        let (mut slow, mut fast) = (Vec::new(), Vec::new());
+        let slow_stats = run(input, &mut slow, false).unwrap();
+        let fast_stats = process_fast(input, &mut fast).unwrap();
+
+        assert!(!slow.is_empty());
+        assert_eq!(slow, fast);
+        // ↑ Vec<u8> == Vec<u8> compares every byte
+        assert_eq!(slow_stats, fast_stats);
+    }
+
+    // Counts checked against `printf 'hello world\nfoo\n' | wc` → "      2       3      16"
+    #[test]
+    fn wc_summary_matches_real_wc() {
+        let stats = run(&b"hello world\nfoo\n"[..], io::sink(), false).unwrap();
+        assert_eq!(format_wc(&stats, None), "      2       3      16");
+        assert_eq!(format_wc(&stats, Some("in.txt")), "      2       3      16 in.txt");
+    }
+
+    // A writer standing in for stdout after `head` has closed the pipe
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+            // ↑ Exactly what writing to a closed pipe returns (EPIPE)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn broken_pipe_is_a_clean_exit() {
+        let result = run(&b"one\ntwo\n"[..], ClosedPipe, false);
+        assert!(matches!(&result, Err(e) if e.kind() == io::ErrorKind::BrokenPipe));
+        assert_eq!(exit_code(&result), 0);
+        // ↑ The error comes back out of run() (no panic), and we'd exit cleanly
+    }
+}
@@ -0,0 +1,149 @@
+// ============================================================================
+// Example 6: Tee - Sending the same output to TWO places
+// ============================================================================
+//
+// WHAT IS TEE?
+// The Unix `tee` command copies its stdin to stdout AND to a file:
+//   some_program | tee log.txt | next_program
+//                   ↓
+//   Shaped like a T-pipe fitting: data flows straight through,
+//   and a copy branches off into log.txt
+//
+// Here we build the same idea as a Rust type: a writer that wraps two
+// other writers and forwards every write to both of them.
+//
+// Try running:
+//   cargo run --example ex06_tee
+//   cat tee_output.txt
+//   ↑ The file holds exactly what was printed to the terminal
+//
+//   cargo run --example ex06_tee > /dev/null
+//   ↑ Throw away stdout - the file still gets its copy
+//
+//   cargo test --example ex06_tee
+//   ↑ Tees into two Vec<u8>s in memory and checks both got the same bytes
+
+// ----------------------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------------------
+use std::fs::File;
+use std::io::{self, Write};
+// ↑   ↑   ↑    ↑     ↑
+// │   │   │    │     └─ Write trait - we IMPLEMENT it for Tee below
+// │   │   │    └─────── Import io module itself (for io::Result, io::stdout)
+// │   │   └──────────── Multiple imports from io
+// │   └──────────────── Path separator
+// └──────────────────── Standard library
+
+// ----------------------------------------------------------------------------
+// THE TEE TYPE
+// ----------------------------------------------------------------------------
+pub struct Tee<A: Write, B: Write> {
+// ↑          ↑  ↑       ↑
+// │          │  │       └─ B = the second destination (any writer)
+// │          │  └───────── "A must implement Write"
+// │          └──────────── A = the first destination (any writer)
+// └─────────────────────── Generic struct: works with stdout, files, Vec<u8>, ...
+    pub first: A,
+    pub second: B,
+}
+
+impl<A: Write, B: Write> Tee<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Tee { first, second }
+    }
+}
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+// ↑                    ↑
+// │                    └─ Implementing Write means Tee works with write!,
+// │                       writeln!, BufWriter, io::copy ... anything that takes a writer
+// └────────────────────── impl block for the trait
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.first.write_all(buf)?;
+        self.second.write_all(buf)?;
+        // ↑           ↑
+        // │           └─ write_all keeps writing until EVERY byte is out
+        // │              (plain write() may only write part of the buffer!)
+        // └───────────── ? = stop at the first error and return it
+        //
+        // Both destinations got the whole buffer, so report all of it written
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.first.flush()?;
+        self.second.flush()
+        // Flush BOTH - otherwise one side could still be sitting in a buffer
+    }
+}
+
+fn main() -> io::Result<()> {
+// ↑         ↑
+// │         └─ main can return a Result: an Err prints the error and exits with code 1
+// └─────────── Entry point
+
+    eprintln!("=== Tee Demo ===");
+    eprintln!("Writing to stdout AND tee_output.txt\n");
+    // Diagnostics to stderr - they are NOT part of the tee'd output
+
+    let file = File::create("tee_output.txt")?;
+    let mut tee = Tee::new(io::stdout().lock(), file);
+    //                     ↑                     ↑
+    //                     │                     └─ Second destination: the file
+    //                     └─────────────────────── First destination: stdout (fd 1)
+
+    for i in 1..=5 {
+        writeln!(tee, "Line {}: written once, stored twice", i)?;
+        // ONE writeln! call → bytes land in BOTH stdout and the file
+    }
+    tee.flush()?;
+
+    eprintln!("\nDone! Compare with: cat tee_output.txt");
+    Ok(())
+}
+
+// ============================================================================
+// KEY TAKEAWAYS:
+// ============================================================================
+//
+// 1. Write is just a trait - ANY type can become a writer by implementing
+//    write() and flush()
+//
+// 2. Writers compose: Tee wraps two writers, and could even wrap another Tee
+//    to fan out to three or more destinations
+//
+// 3. Use write_all (not write) when forwarding, so no bytes get dropped
+//
+// 4. Generic code (Tee<A, B>) doesn't care WHERE the bytes go -
+//    stdout, a file, a network socket, or a Vec<u8> in memory
+
+// ============================================================================
+// TESTS: Two Vec<u8>s instead of stdout and a file
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_sides_get_every_byte() {
+        let mut tee = Tee::new(Vec::new(), Vec::new());
+        writeln!(tee, "Line {}", 1).unwrap();
+        tee.write_all(b"caf\xc3\xa9\n").unwrap();
+        tee.flush().unwrap();
+
+        assert_eq!(tee.first, b"Line 1\ncaf\xc3\xa9\n");
+        assert_eq!(tee.first, tee.second);
+    }
+
+    #[test]
+    fn a_tee_of_tees_fans_out_to_three() {
+        let mut tee = Tee::new(Vec::new(), Tee::new(Vec::new(), Vec::new()));
+        io::copy(&mut &b"same everywhere"[..], &mut tee).unwrap();
+
+        assert_eq!(tee.first, b"same everywhere");
+        assert_eq!(tee.second.first, tee.first);
+        assert_eq!(tee.second.second, tee.first);
+    }
+}
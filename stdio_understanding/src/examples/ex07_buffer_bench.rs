@@ -0,0 +1,110 @@
+// ============================================================================
+// Example 7: Buffer Benchmark - MEASURING what buffering saves
+// ============================================================================
+//
+// ex03_buffering explains that buffered writes are much faster.
+// This example checks that claim with a stopwatch.
+//
+// We write the same 100,000 lines twice:
+//   1. println! - locks stdout on EVERY call, line-buffered (a flush per \n)
+//   2. BufWriter around ONE StdoutLock - locks once, flushes only when its
+//      8 KB buffer fills up
+//
+// Timings go to stderr, so stdout carries only the lines themselves.
+//
+// Try running (release mode - debug builds hide the real difference):
+//   cargo run --release --example ex07_buffer_bench > /dev/null
+//   ↑ Throw the lines away, keep the timings
+//
+//   cargo run --release --example ex07_buffer_bench > lines.txt
+//   ↑ To a file, stdout is not a terminal - compare the numbers!
+//
+//   cargo run --release --example ex07_buffer_bench
+//   ↑ To the terminal - drawing 200,000 lines dominates both timings
+
+// ----------------------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------------------
+use std::io::{self, BufWriter, Write};
+// ↑   ↑   ↑    ↑     ↑          ↑
+// │   │   │    │     │          └─ Write trait (writeln!, flush)
+// │   │   │    │     └──────────── BufWriter collects writes in memory first
+// │   │   │    └────────────────── Import io module itself
+// │   │   └─────────────────────── Multiple imports from io
+// │   └─────────────────────────── Path separator
+// └─────────────────────────────── Standard library
+
+use std::time::{Duration, Instant};
+// ↑              ↑         ↑
+// │              │         └─ Instant::now() = a point in time (a stopwatch start)
+// │              └─────────── Duration = a length of time (what elapsed() returns)
+// └────────────────────────── Standard library time module
+
+const LINES: usize = 100_000;
+//                   ↑
+//                   └─ Underscores in numbers are just for readability
+
+// ----------------------------------------------------------------------------
+// METHOD 1: println! for every line
+// ----------------------------------------------------------------------------
+fn with_println() -> Duration {
+    let start = Instant::now();
+    for i in 0..LINES {
+        println!("line {}", i);
+        // Each call: lock stdout → format → write → (line-buffered) flush → unlock
+    }
+    start.elapsed()
+}
+
+// ----------------------------------------------------------------------------
+// METHOD 2: One lock, one BufWriter, flush at the end
+// ----------------------------------------------------------------------------
+fn with_bufwriter() -> io::Result<Duration> {
+    let start = Instant::now();
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    //            ↑              ↑
+    //            │              └─ Lock stdout ONCE for the whole loop
+    //            └──────────────── Wrap it so writes pile up in an 8 KB buffer
+
+    for i in 0..LINES {
+        writeln!(out, "line {}", i)?;
+        // Usually just a memcpy into the buffer - no system call at all
+    }
+    out.flush()?;
+    // Push out whatever is left in the buffer - stop the clock AFTER this
+
+    Ok(start.elapsed())
+}
+
+fn main() -> io::Result<()> {
+    eprintln!("=== Buffer Benchmark: {} lines each ===\n", LINES);
+
+    let slow = with_println();
+    let fast = with_bufwriter()?;
+
+    eprintln!("println! (lock + flush per line): {:>10.2?}", slow);
+    eprintln!("BufWriter<StdoutLock>:            {:>10.2?}", fast);
+    //                                           ↑
+    //                                           └─ {:?} on a Duration prints "12.34ms"
+    //                                              .2 = two decimals, >10 = right-align
+
+    if fast > Duration::ZERO {
+        eprintln!("\nBufWriter was {:.1}x faster", slow.as_secs_f64() / fast.as_secs_f64());
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// WHAT TO LOOK FOR:
+// ============================================================================
+//
+// - To /dev/null or a file, BufWriter should win by a wide margin:
+//   far fewer system calls, and no lock/unlock per line
+//
+// - To a terminal, both are slow: the terminal redrawing text costs far
+//   more than the system calls, so the gap shrinks
+//
+// - In a debug build, formatting overhead hides much of the difference -
+//   always benchmark with --release
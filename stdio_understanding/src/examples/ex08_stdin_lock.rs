@@ -0,0 +1,116 @@
+// ============================================================================
+// Example 8: Holding the stdin lock - ONE lock for MANY reads
+// ============================================================================
+//
+// ex01 reads a single line, so io::stdin().lock() once is all it needs.
+// Reading several lines, it's tempting to write this:
+//
+//   for _ in 0..3 {
+//       io::stdin().read_line(&mut line)?;   // lock, read, unlock - every time
+//   }
+//
+// Every call locks stdin, reads, and unlocks again. That works, but:
+//   - each lock/unlock costs something (it's a mutex)
+//   - between two calls ANOTHER thread may grab stdin and take a line
+//
+// The fix: lock once, keep the lock, read everything through it.
+//
+// Try running:
+//   printf 'one\ntwo\nthree\nfour\n' | cargo run --example ex08_stdin_lock
+//   ↑ Reads the first 3 lines; "four" is never read
+//
+//   printf 'only\n' | cargo run --example ex08_stdin_lock
+//   ↑ Input ends early - we get 1 line, not an error
+//
+//   cargo test --example ex08_stdin_lock
+//   ↑ Runs read_n_lines on in-memory input, no typing needed
+
+// ----------------------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------------------
+use std::io::{self, BufRead};
+// ↑   ↑   ↑    ↑     ↑
+// │   │   │    │     └─ BufRead trait (adds read_line() to the lock)
+// │   │   │    └─────── Import io module itself
+// │   │   └──────────── Multiple imports from io
+// │   └──────────────── Path separator
+// └──────────────────── Standard library
+
+const LINES_TO_READ: usize = 3;
+
+// ----------------------------------------------------------------------------
+// READ_N_LINES: Up to n lines from a reader we already hold
+// ----------------------------------------------------------------------------
+fn read_n_lines<R: BufRead>(r: &mut R, n: usize) -> io::Result<Vec<String>> {
+// ↑           ↑            ↑
+// │           │            └─ &mut R: we BORROW the reader - the caller keeps it
+// │           │               (and keeps its lock) after we return
+// │           └────────────── Any BufRead: a StdinLock, a file, bytes in memory
+// └────────────────────────── Fewer than n lines if the input ends first
+    let mut lines = Vec::with_capacity(n);
+    let mut line = String::new();
+    // ↑ ONE String reused for every read - read_line appends, so clear() it each time
+
+    while lines.len() < n {
+        line.clear();
+        if r.read_line(&mut line)? == 0 {
+            break;
+            // 0 bytes read = end of input (EOF), not an error
+        }
+        lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+    }
+    Ok(lines)
+}
+
+fn main() -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut lock = stdin.lock();
+    // ↑          ↑
+    // │          └─ Lock ONCE, here - held until `lock` is dropped at the end of main
+    // └──────────── `stdin` gets its own variable so it's obvious what is locked
+
+    eprintln!("Type up to {} lines (Ctrl+D to stop early):", LINES_TO_READ);
+    let lines = read_n_lines(&mut lock, LINES_TO_READ)?;
+    //                       ↑
+    //                       └─ Lend the lock out; we still hold it afterwards
+
+    for (i, line) in lines.iter().enumerate() {
+        println!("{}: {}", i + 1, line);
+    }
+    eprintln!("Read {} line(s) through one lock", lines.len());
+    Ok(())
+}
+
+// ============================================================================
+// EXPERIMENTS TO TRY:
+// ============================================================================
+//
+// 1. Move `let mut lock = stdin.lock();` inside a loop and call read_n_lines(&mut lock, 1)
+//    three times. Same output - but now it's three lock/unlock pairs.
+//
+// 2. Call io::stdin().read_line(...) while `lock` is still alive.
+//    Single-threaded, that's a DEADLOCK: the second lock waits for the first forever.
+//
+// 3. Pipe in a large file with LINES_TO_READ = 1_000_000 and time both versions
+//    (compare ex07_buffer_bench, which does the same for stdout).
+
+// ============================================================================
+// TEST: Three lines from memory, no terminal involved
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_n_lines_stops_at_n_and_carries_on_where_it_stopped() {
+        let mut input = io::Cursor::new("first\nsecond\r\nthird\nfourth\n");
+        //              ↑
+        //              └─ Cursor over a string implements BufRead, just like a StdinLock
+
+        assert_eq!(read_n_lines(&mut input, 3).unwrap(), ["first", "second", "third"]);
+        assert_eq!(read_n_lines(&mut input, 3).unwrap(), ["fourth"]);
+        // ↑ Same reader again: it carries on where the first call stopped - and
+        //   running out of input early is fewer lines, not an error
+        assert!(read_n_lines(&mut input, 3).unwrap().is_empty());
+    }
+}
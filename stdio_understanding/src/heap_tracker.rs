@@ -0,0 +1,156 @@
+// HEAP TRACKER - Watching the heap grow, one allocation at a time
+//
+// memory_demo.rs says a String is "stack pointer + heap data". This file
+// counts the heap side: every allocation in the program goes through our
+// allocator, which adds up the bytes before handing the work to the system.
+//
+// Build and run like the other memory demos:
+//   rustc src/heap_tracker.rs -o heap_tracker && ./heap_tracker
+// and test with:
+//   rustc --test src/heap_tracker.rs -o heap_tracker_test && ./heap_tracker_test
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Wraps the system allocator and counts bytes in and out.
+// The counts are per thread: any thread may allocate at any time, and a
+// measurement should only see its own thread's work (the test runner
+// allocates on other threads while a test is running).
+struct CountingAllocator;
+
+thread_local! {
+    // `const` + a plain Cell: no lazy setup, so reading these never allocates
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+    static FREED: Cell<usize> = const { Cell::new(0) };
+}
+
+// Adds `n` to a counter; does nothing once the thread is being torn down
+fn add(counter: &'static std::thread::LocalKey<Cell<usize>>, n: usize) {
+    let _ = counter.try_with(|c| c.set(c.get() + n));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            add(&ALLOCATED, layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        add(&FREED, layout.size());
+        unsafe { System.dealloc(ptr, layout) }
+    }
+    // realloc isn't overridden: the default one calls alloc + dealloc above,
+    // so growing a Vec shows up as a new block plus a freed old one
+}
+
+// Every Box, String, Vec, ... in this program now goes through CountingAllocator
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// (total allocated, total freed) so far on this thread
+fn snapshot() -> (usize, usize) {
+    (ALLOCATED.with(Cell::get), FREED.with(Cell::get))
+}
+
+// Runs `f` and returns what it allocated and freed.
+// Nothing may print inside `f` - println! can allocate its own buffer.
+fn measure<T>(f: impl FnOnce() -> T) -> (T, usize, usize) {
+    let (alloc_before, freed_before) = snapshot();
+    let value = f();
+    let (alloc_after, freed_after) = snapshot();
+    (value, alloc_after - alloc_before, freed_after - freed_before)
+}
+
+fn main() {
+    println!("=== HEAP TRACKING DEMO ===");
+    println!();
+
+    let (allocated, freed) = snapshot();
+    println!("Before: {} bytes allocated, {} freed, {} live", allocated, freed, allocated - freed);
+    println!("(the runtime and the first println! already allocated a little)");
+    println!();
+
+    // A String copies its text to the heap: exactly len bytes
+    let (s, allocated, _) = measure(|| String::from("hello"));
+    println!("String::from(\"hello\"):      {:>5} bytes allocated (len {}, cap {})", allocated, s.len(), s.capacity());
+    // ↑ 5 ASCII bytes, no terminator like C strings
+
+    // A Vec asks for capacity * size_of::<T>() bytes
+    let (v, allocated, _) = measure(|| vec![0u8; 1000]);
+    println!("vec![0u8; 1000]:            {:>5} bytes allocated (cap {})", allocated, v.capacity());
+
+    // Growing one element at a time reallocates as capacity doubles
+    let (grown, allocated, freed) = measure(|| {
+        let mut grown = Vec::new();
+        for i in 0..1000u32 {
+            grown.push(i);
+        }
+        grown
+    });
+    println!(
+        "push 1000 u32s one by one:  {:>5} bytes allocated, {:>5} freed (cap {} x 4 bytes)",
+        allocated,
+        freed,
+        grown.capacity()
+    );
+    println!("  ↑ Every regrow copies into a bigger block and frees the old one");
+
+    // Stack values never touch the allocator
+    let (_, allocated, _) = measure(|| [0u8; 1000]);
+    println!("[0u8; 1000] (array):        {:>5} bytes allocated (it lives on the stack)", allocated);
+
+    // Dropping hands the bytes back
+    let (_, _, freed) = measure(|| drop(s));
+    println!("drop(s):                    {:>5} bytes freed", freed);
+
+    println!();
+    let (allocated, freed) = snapshot();
+    println!("After: {} bytes allocated, {} freed, {} live", allocated, freed, allocated - freed);
+    drop((v, grown));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_string_allocates_exactly_its_bytes_and_frees_them_on_drop() {
+        let (s, allocated, _) = measure(|| String::from("hello"));
+        assert_eq!(allocated, 5); // 5 ASCII bytes, no terminator like C strings
+
+        let (_, _, freed) = measure(|| drop(s));
+        assert_eq!(freed, 5);
+    }
+
+    #[test]
+    fn a_vec_allocates_capacity_times_element_size() {
+        let (_, allocated, _) = measure(|| vec![0u8; 1000]);
+        assert_eq!(allocated, 1000);
+
+        let (_, allocated, _) = measure(|| vec![0u32; 1000]);
+        assert_eq!(allocated, 4000);
+    }
+
+    #[test]
+    fn growing_a_vec_frees_every_old_block() {
+        let (grown, allocated, freed) = measure(|| {
+            let mut grown = Vec::new();
+            for i in 0..1000u32 {
+                grown.push(i);
+            }
+            grown
+        });
+        // Everything still live is the final block; the rest were regrows
+        assert_eq!(allocated - freed, grown.capacity() * 4);
+        assert!(freed > 0);
+    }
+
+    #[test]
+    fn an_array_never_touches_the_allocator() {
+        let (_, allocated, freed) = measure(|| [0u8; 1000]);
+        assert_eq!((allocated, freed), (0, 0));
+    }
+}
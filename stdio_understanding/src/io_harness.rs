@@ -0,0 +1,189 @@
+// ============================================================================
+// io_harness: The stdin/stdout plumbing every example needs
+// ============================================================================
+//
+// Every example locks stdout, reads stdin line by line, and passes errors up.
+// These helpers do that once, so an example can say WHAT it does
+// instead of repeating HOW to hook up the streams.
+
+use std::io::{self, BufRead, StdoutLock, Write};
+// ↑   ↑   ↑    ↑     ↑        ↑           ↑
+// │   │   │    │     │        │           └─ Write trait (write!, flush)
+// │   │   │    │     │        └───────────── StdoutLock = stdout, held by us until dropped
+// │   │   │    │     └────────────────────── BufRead trait (adds lines())
+// │   │   │    └──────────────────────────── Import io module itself
+// │   │   └───────────────────────────────── Multiple imports from io
+// │   └───────────────────────────────────── Path separator
+// └───────────────────────────────────────── Standard library
+
+use crate::error::StdioError;
+// Line reading and the progress bar return this instead of io::Error - see src/error.rs
+
+// ----------------------------------------------------------------------------
+// WITH_LOCKED_STDOUT: Lock stdout once, hand it to `f`
+// ----------------------------------------------------------------------------
+/// Runs `f` with stdout locked for its whole duration
+///
+/// One lock instead of one per `println!`, and no other thread can
+/// interleave its output with ours. The lock is released when `f` returns.
+pub fn with_locked_stdout<F, T>(f: F) -> io::Result<T>
+//                        ↑  ↑
+//                        │  └─ T = whatever `f` hands back on success
+//                        └──── F = the closure that does the writing
+where
+    F: FnOnce(&mut StdoutLock<'static>) -> io::Result<T>,
+    //  ↑           ↑
+    //  │           └─ 'static: io::stdout() is global, so its lock can live as long as we like
+    //  └───────────── FnOnce: we call it exactly once
+{
+    let mut out = io::stdout().lock();
+    f(&mut out)
+    // `out` is dropped here → the lock is released
+}
+
+// ----------------------------------------------------------------------------
+// READ_ALL_LINES: Collect every line of a reader
+// ----------------------------------------------------------------------------
+/// Reads every line from `input`, without the line endings
+///
+/// Works on any `BufRead` - stdin, a file, or bytes in memory. A trailing
+/// newline does not produce an extra empty line, and `\r\n` is handled too:
+/// ```
+/// use stdio_learning::io_harness::read_all_lines;
+///
+/// let lines = read_all_lines(&b"apple\nzebra\nbanana"[..]).unwrap();
+/// assert_eq!(lines, ["apple", "zebra", "banana"]);
+///
+/// let lines = read_all_lines(&b"apple\r\nzebra\n"[..]).unwrap();
+/// assert_eq!(lines, ["apple", "zebra"]);
+///
+/// assert!(read_all_lines(&b""[..]).unwrap().is_empty());
+/// ```
+///
+/// A line that isn't valid UTF-8 is `StdioError::InvalidUtf8`, with its line number.
+pub fn read_all_lines<R: BufRead>(input: R) -> Result<Vec<String>, StdioError> {
+    input
+        .lines()
+        //↑ Iterator of io::Result<String>, one per line
+        .enumerate()
+        .map(|(index, line)| {
+            line.map_err(|e| match e.kind() {
+                io::ErrorKind::InvalidData => StdioError::InvalidUtf8 { line: index + 1 },
+                // ↑ How lines() reports bytes that aren't UTF-8
+                _ => StdioError::Io(e),
+            })
+        })
+        .collect()
+        //↑ collect() into Result<Vec<_>, _> stops at the FIRST error
+}
+
+// ----------------------------------------------------------------------------
+// READ_ALL_LINES_LOSSY: Same, but bad UTF-8 can't stop us
+// ----------------------------------------------------------------------------
+/// Like `read_all_lines`, but invalid UTF-8 becomes `�` instead of an error
+///
+/// `lines()` refuses a line that isn't valid UTF-8, so one bad byte in real-world
+/// data would end the whole read. Here each line is read as raw bytes first:
+/// ```
+/// use stdio_learning::io_harness::read_all_lines_lossy;
+///
+/// let input: &[u8] = b"caf\xc3\xa9\nbad \xff byte\nok\n";
+/// //                       ^^^^^^^^       ^^^^
+/// //                       é in UTF-8     never valid in UTF-8
+/// let lines = read_all_lines_lossy(input).unwrap();
+/// assert_eq!(lines, ["café", "bad \u{FFFD} byte", "ok"]);
+/// ```
+pub fn read_all_lines_lossy<R: BufRead>(mut input: R) -> Result<Vec<String>, StdioError> {
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+
+    while input.read_until(b'\n', &mut buf)? > 0 {
+    //          ↑          ↑       ↑         ↑
+    //          │          │       │         └─ Bytes read - 0 means EOF
+    //          │          │       └─────────── Appends raw bytes, no UTF-8 check at all
+    //          │          └─────────────────── Stop after this byte (the \n is kept)
+    //          └────────────────────────────── BufRead's byte-level cousin of read_line
+        let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        // Drop the line ending ourselves - lines() did this for us
+
+        lines.push(String::from_utf8_lossy(line).into_owned());
+        //         ↑
+        //         └─ Valid UTF-8 is copied as is; each bad sequence becomes U+FFFD (�)
+        buf.clear();
+    }
+    Ok(lines)
+}
+
+// ----------------------------------------------------------------------------
+// PROGRESS_BAR: Draws one frame like "\r[##########----------]  50%"
+// ----------------------------------------------------------------------------
+const BAR_WIDTH: usize = 20;
+
+/// Draws one progress bar frame, starting with `\r` so the next frame overwrites it
+///
+/// Values above 100 are treated as 100. Flushes, since no `\n` is written:
+/// ```
+/// use stdio_learning::io_harness::progress_bar;
+///
+/// let mut out = Vec::new();
+/// progress_bar(&mut out, 50).unwrap();
+/// assert_eq!(out, b"\r[##########----------]  50%");
+/// ```
+pub fn progress_bar<W: Write>(out: &mut W, percent: u8) -> Result<(), StdioError> {
+// ↑                ↑          ↑               ↑
+// │                │          │               └─ Values above 100 are treated as 100
+// │                │          └───────────────── Any writer - stdout, or a Vec<u8> in a test
+// │                └──────────────────────────── W must implement Write
+// └───────────────────────────────────────────── Generic function
+
+    let percent = percent.min(100);
+    let filled = BAR_WIDTH * percent as usize / 100;
+    //           ↑
+    //           └─ Multiply BEFORE dividing, or integer division rounds to 0
+
+    write!(
+        out,
+        "\r[{}{}] {:>3}%",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled),
+        percent
+    )?;
+    // ↑ ? turns the io::Error into StdioError::Io (that's the #[from])
+    out.flush()?;
+    // ↑ No \n was written, so flush - otherwise the frame waits in the buffer
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// CAPTURE: Run code against in-memory stdout/stderr and read what it wrote
+// ----------------------------------------------------------------------------
+/// Runs `f` with two `Vec<u8>` writers standing in for stdout and stderr,
+/// and returns what landed in each as text
+///
+/// An example written as `run(out, err)` instead of `println!`/`eprintln!`
+/// can then check its own stream split:
+/// ```
+/// use std::io::Write;
+/// use stdio_learning::io_harness::capture;
+///
+/// let (out, err) = capture(|out, err| {
+///     writeln!(out, "data")?;
+///     writeln!(err, "diagnostic")
+/// })
+/// .unwrap();
+/// assert_eq!(out, "data\n");
+/// assert_eq!(err, "diagnostic\n");
+/// ```
+pub fn capture<F>(f: F) -> io::Result<(String, String)>
+where
+    F: FnOnce(&mut Vec<u8>, &mut Vec<u8>) -> io::Result<()>,
+    //        ↑
+    //        └─ Vec<u8> implements Write: every write just appends bytes
+{
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    f(&mut out, &mut err)?;
+    Ok((String::from_utf8_lossy(&out).into_owned(), String::from_utf8_lossy(&err).into_owned()))
+    //  ↑
+    //  └─ Lossy: invalid UTF-8 shows up as � instead of failing the capture
+}
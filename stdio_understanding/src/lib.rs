@@ -0,0 +1,9 @@
+// stdio Learning Project - Shared helpers for the examples
+//
+// The examples (src/examples/) can pull these in with:
+//   use stdio_learning::io_harness::read_all_lines;
+//
+// Keeping the plumbing here lets each example focus on its one concept.
+
+pub mod error;
+pub mod io_harness;
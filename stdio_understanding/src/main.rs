@@ -18,9 +18,12 @@ fn main() {
     println!();
     println!("  1. ex01_basic_stdio  - stdin/stdout basics, reading input");
     println!("  2. ex02_stderr_demo  - Understanding stderr vs stdout");
-    println!("  3. ex03_buffering    - How buffering works (+ hands-on exercise)");
+    println!("  3. ex03_buffering    - How buffering works (+ progress bar)");
     println!("  4. ex04_file_io      - File I/O vs stdio comparison");
     println!("  5. ex05_pipes        - Building pipe-friendly programs");
+    println!("  6. ex06_tee          - Writing to two destinations at once");
+    println!("  7. ex07_buffer_bench - Timing println! vs BufWriter");
+    println!("  8. ex08_stdin_lock   - Reading many lines through one stdin lock");
     println!();
     println!("▶️  Run examples with:");
     println!("   cargo run --example ex01_basic_stdio");
@@ -28,7 +31,7 @@ fn main() {
     println!("💡 Tips:");
     println!("   - Try redirecting: cargo run --example ex02_stderr_demo > out.txt");
     println!("   - Try piping: echo 'test' | cargo run --example ex05_pipes");
-    println!("   - Watch for TODO(human) comments for hands-on practice!");
+    println!("   - Watch for EXPERIMENT comments for hands-on practice!");
     println!();
 
     print!("Press Enter to continue...");
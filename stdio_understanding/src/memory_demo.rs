@@ -1,4 +1,10 @@
 // MEMORY DEMO - Where bits actually live in RAM
+//
+// Addresses change every run. For output that stays the same (screenshots, docs):
+//   rustc src/memory_demo.rs -o memory_demo && ./memory_demo --relative
+// prints each stack address as an offset from x's instead.
+// Test with:
+//   rustc --test src/memory_demo.rs -o memory_demo_test && ./memory_demo_test
 
 use std::mem;
 
@@ -7,17 +13,30 @@ fn main() {
     let x: u32 = 42;           // 4 bytes on stack
     let y: u64 = 123456789;    // 8 bytes on stack  
     let s: String = String::from("hello");  // Stack pointer + heap data
+
+    let x_addr = &x as *const u32 as usize;
+    let y_addr = &y as *const u64 as usize;
+    let s_addr = &s as *const String as usize;
+
+    // --relative: "+0", "+4", ... from x instead of absolute addresses
+    let relative = std::env::args().any(|arg| arg == "--relative");
+    let offsets = relative_offsets(&[x_addr, y_addr, s_addr]);
+    let show = |i: usize, addr: usize| {
+        if relative { format!("{:+}", offsets[i]) } else { format!("{:#x}", addr) }
+    };
+    // The heap is nowhere near the stack - an offset to it would mean nothing
+    let heap = if relative { "heap".to_string() } else { format!("{:p}", s.as_ptr()) };
     
     println!("=== MEMORY LAYOUT DEMO ===");
     println!();
     
     // Show where each variable lives in memory
     println!("Variable locations:");
-    println!("x (u32):        {:p} = {}", &x as *const u32 as *const (), x);
-    println!("y (u64):        {:p} = {}", &y as *const u64 as *const (), y);
-    println!("s (String):     {:p} = {} (ptr: {:p}, len: {}, cap: {})", 
-             &s as *const String as *const (), s,
-             s.as_ptr(), s.len(), s.capacity());
+    println!("x (u32):        {} = {}", show(0, x_addr), x);
+    println!("y (u64):        {} = {}", show(1, y_addr), y);
+    println!("s (String):     {} = {} (ptr: {}, len: {}, cap: {})", 
+             show(2, s_addr), s,
+             heap, s.len(), s.capacity());
     
     println!();
     println!("=== SIZE OF EACH TYPE ===");
@@ -28,8 +47,10 @@ fn main() {
     
     println!();
     println!("=== BIT PATTERNS IN RAM ===");
-    show_bytes("x = 42", &x as *const u32 as *const u8, mem::size_of::<u32>());
-    show_bytes("y = 123456789", &y as *const u64 as *const u8, mem::size_of::<u64>());
+    dump_bytes("x = 42", &x);
+    dump_bytes("y = 123456789", &y);
+    dump_bytes("z = 1.5 (f64)", &1.5f64);
+    dump_bytes("Point { x: 1, y: 2 }", &Point { x: 1, y: 2 });
     
     // Show the actual heap data
     println!("String data on heap:");
@@ -44,15 +65,25 @@ fn main() {
     println!("Stack (simplified):");
     println!("Address:        Bytes:                    Meaning:");
     
-    let x_addr = &x as *const u32 as usize;
-    let y_addr = &y as *const u64 as usize;
-    let s_addr = &s as *const String as usize;
+    // Same 16-character column either way
+    let column = |i: usize, addr: usize| {
+        if relative { format!("{:>16}", show(i, addr)) } else { format!("{:016x}", addr) }
+    };
+    println!("{}: {:02x} {:02x} {:02x} {:02x}          x = 42", 
+             column(0, x_addr), 0x2a, 0x00, 0x00, 0x00);
+    println!("{}: {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x}  y = 123456789", 
+             column(1, y_addr), 0x15, 0xcd, 0x5b, 0x07, 0x00, 0x00, 0x00, 0x00);
+    println!("{}: ?? ?? ?? ?? ?? ?? ?? ??  s = String struct", column(2, s_addr));
     
-    println!("{:016x}: {:02x} {:02x} {:02x} {:02x}          x = 42", 
-             x_addr, 0x2a, 0x00, 0x00, 0x00);
-    println!("{:016x}: {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x}  y = 123456789", 
-             y_addr, 0x15, 0xcd, 0x5b, 0x07, 0x00, 0x00, 0x00, 0x00);
-    println!("{:016x}: ?? ?? ?? ?? ?? ?? ?? ??  s = String struct", s_addr);
+    println!();
+    println!("=== STRUCT LAYOUT (size, alignment, padding) ===");
+    describe_layout::<Location>("Location (cp6)");
+    print_offsets!(Location, x, y, z, area, snow);
+    println!();
+    describe_layout::<Padded>("Padded (repr(C))");
+    print_offsets!(Padded, a, b, c);
+    println!("  fields add up to {} bytes; the rest is padding", 1 + 4 + 2);
+    println!();
     
     // Wait for user to see the output
     println!("\nPress Enter to continue...");
@@ -60,12 +91,98 @@ fn main() {
     std::io::stdin().read_line(&mut input).ok();
 }
 
-fn show_bytes(name: &str, ptr: *const u8, size: usize) {
+// Location and Snowball from CP_with_rust/cp6.rs, copied here to look at their layout
+// (never constructed - only measured)
+#[allow(dead_code)]
+struct Snowball(i64);
+
+#[allow(dead_code)]
+struct Location {
+    x: f64,
+    y: f64,
+    z: f64,
+    area: f64,
+    snow: Snowball,
+}
+
+// u8, u32, u16 in declaration order: the u32 must start on a 4-byte boundary,
+// so repr(C) inserts padding after `a` and after `c`
+#[allow(dead_code)]
+#[repr(C)]
+struct Padded {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+// Each address as a signed distance from the first one:
+//   [0x1000, 0x1008, 0x0ff0] -> [0, 8, -16]
+// Stack variables can sit below the first as easily as above it, hence isize
+fn relative_offsets(addrs: &[usize]) -> Vec<isize> {
+    let Some(&first) = addrs.first() else {
+        return Vec::new();
+    };
+    addrs.iter().map(|&addr| addr.wrapping_sub(first) as isize).collect()
+    //                            ^^^^^^^^^^^^
+    //                            Plain `-` would panic (in debug) when addr < first;
+    //                            wrapping then reinterpreting as isize gives the negative offset
+}
+
+// Prints how big a type is and what address boundary it has to start on
+fn describe_layout<T>(name: &str) {
+    println!(
+        "{}: size {} bytes, align {} bytes",
+        name,
+        mem::size_of::<T>(),
+        mem::align_of::<T>()
+    );
+}
+
+// print_offsets!(Type, field1, field2, ...) prints where each field starts
+macro_rules! print_offsets {
+    ($ty:ty, $($field:ident),+) => {
+        $(
+            println!("  {:<6} at offset {}", stringify!($field), mem::offset_of!($ty, $field));
+        )+
+    };
+}
+use print_offsets;
+
+// A small struct to look at - two u16 fields, laid out in order, no padding
+#[repr(C)]
+struct Point {
+    x: u16,
+    y: u16,
+}
+
+// Prints the raw bytes of any value: dump_bytes("x", &x)
+// T must have no padding bytes (padding is uninitialized, so reading it is UB)
+fn dump_bytes<T>(name: &str, value: &T) {
+    let size = mem::size_of::<T>();
+    // SAFETY: the pointer comes from a live reference, so it is non-null, aligned
+    // and valid for size_of::<T>() bytes for as long as `value` is borrowed.
+    let bytes = unsafe { std::slice::from_raw_parts(value as *const T as *const u8, size) };
+
     print!("{}: ", name);
-    unsafe {
-        for i in 0..size {
-            print!("{:02x} ", *ptr.add(i));
-        }
+    for byte in bytes {
+        print!("{:02x} ", byte);
     }
     println!("({} bytes)", size);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hand-checked offsets: the first is always +0, and lower addresses come out negative
+    #[test]
+    fn relative_offsets_are_signed_distances_from_the_first() {
+        assert_eq!(relative_offsets(&[0x1000, 0x1008, 0x0ff0]), [0, 8, -16]);
+    }
+
+    #[test]
+    fn relative_offsets_of_one_or_none() {
+        assert_eq!(relative_offsets(&[0x7ffc_0000]), [0]);
+        assert!(relative_offsets(&[]).is_empty());
+    }
+}
@@ -1,6 +1,131 @@
 // MEMORY VALIDATOR - How to cross-validate bit patterns
+//
+// Run, and test the helpers, with:
+//   rustc src/memory_validator.rs -o memory_validator && ./memory_validator
+//   rustc --test src/memory_validator.rs -o memory_validator_test && ./memory_validator_test
+//   rustdoc --test src/memory_validator.rs
 
-use std::mem;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
+
+// Which end of a multi-byte value is stored at the lowest address
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Endianness {
+    Little, // least significant byte first (x86, most ARM)
+    Big,    // most significant byte first
+}
+
+impl fmt::Display for Endianness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endianness::Little => write!(f, "little-endian"),
+            Endianness::Big => write!(f, "big-endian"),
+        }
+    }
+}
+
+// Compile-time size check for reinterpret: reading ::OK forces the assert
+// to be evaluated when reinterpret::<Src, Dst> is compiled
+struct AssertSameSize<Src, Dst>(PhantomData<(Src, Dst)>);
+
+impl<Src, Dst> AssertSameSize<Src, Dst> {
+    const OK: () = assert!(
+        mem::size_of::<Src>() == mem::size_of::<Dst>(),
+        "reinterpret: source and destination sizes differ"
+    );
+}
+
+// mem::transmute, but a size mismatch is a compile error for ANY types:
+//   let halves: [u16; 2] = unsafe { reinterpret(0x12345678u32) };   // ok: 4 == 4
+//   let oops: u64 = unsafe { reinterpret(0x12345678u32) };          // error[E0080]:
+//       evaluation panicked: reinterpret: source and destination sizes differ
+//
+// Still unsafe: equal sizes don't make every bit pattern valid (e.g. 2u8 as a bool).
+//
+// The rejection, as a doctest (rustdoc --test src/memory_validator.rs). Doctests
+// can't see this file's private items, so the hidden lines repeat the two definitions:
+/// ```compile_fail,E0080
+/// # use std::marker::PhantomData;
+/// # use std::mem::{self, ManuallyDrop};
+/// # struct AssertSameSize<Src, Dst>(PhantomData<(Src, Dst)>);
+/// # impl<Src, Dst> AssertSameSize<Src, Dst> {
+/// #     const OK: () = assert!(mem::size_of::<Src>() == mem::size_of::<Dst>());
+/// # }
+/// # unsafe fn reinterpret<Src, Dst>(src: Src) -> Dst {
+/// #     let () = AssertSameSize::<Src, Dst>::OK;
+/// #     unsafe { mem::transmute_copy(&ManuallyDrop::new(src)) }
+/// # }
+/// let oops: u64 = unsafe { reinterpret::<u32, u64>(0x12345678) }; // 4 bytes into 8
+/// ```
+unsafe fn reinterpret<Src, Dst>(src: Src) -> Dst {
+    #[allow(clippy::let_unit_value)]
+    let () = AssertSameSize::<Src, Dst>::OK;
+    // ManuallyDrop: the bytes now belong to the Dst, so src must not be dropped too
+    unsafe { mem::transmute_copy(&ManuallyDrop::new(src)) }
+}
+
+// Finds out at runtime by looking at how 0x0102 is laid out in memory
+fn host_endianness() -> Endianness {
+    // to_ne_bytes = "native endian" bytes, i.e. exactly what sits in RAM
+    let bytes: [u8; 2] = 0x0102u16.to_ne_bytes();
+    if bytes[0] == 0x02 {
+        Endianness::Little
+    } else {
+        Endianness::Big
+    }
+}
+
+// The raw bytes of any value, exactly as they sit in memory.
+// Only meaningful for types without padding (integers, arrays of them):
+// padding bytes are uninitialized, and reading them is undefined behavior.
+fn bytes_of<T>(value: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) }
+}
+
+// Same type, same bytes? Compares memory, not PartialEq - so it works on
+// types that don't implement PartialEq, and sees 0.0 and -0.0 as different
+fn bytes_equal<T>(a: &T, b: &T) -> bool {
+    bytes_of(a) == bytes_of(b)
+}
+
+// Hamming distance: how many bits you'd have to flip to turn a into b.
+// XOR leaves a 1 wherever the bits differ, count_ones counts them.
+fn bit_diff<T>(a: &T, b: &T) -> u32 {
+    bytes_of(a).iter().zip(bytes_of(b)).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+// Popcount: how many bits are 1. The loop is what count_ones() does for you
+// (usually as a single CPU instruction):
+fn popcount(x: u32) -> u32 {
+    let mut x = x;
+    let mut count = 0;
+    while x != 0 {
+        count += x & 1; // look at the lowest bit...
+        x >>= 1; //        ...then shift the next one down
+    }
+    count
+}
+
+// Which bit indices are 1, lowest first (bit 0 = the 1s place)
+fn bits_set_positions(x: u32) -> Vec<u8> {
+    (0..u32::BITS as u8).filter(|&bit| x & (1 << bit) != 0).collect()
+}
+
+// Classic hexdump grid, 16 bytes per row:
+// 00000000: 78 56 34 12                                      xV4.
+fn hexdump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+            .collect();
+        out.push_str(&format!("{:08x}: {:<47}  {}\n", row * 16, hex.join(" "), ascii));
+    }
+    out
+}
 
 fn main() {
     println!("=== CROSS-VALIDATING BIT PATTERNS ===");
@@ -36,8 +161,9 @@ fn main() {
     
     // METHOD 2: Byte-by-byte analysis
     println!("=== METHOD 2: BYTE-BY-BYTE ANALYSIS ===");
+    let endianness = host_endianness();
     unsafe {
-        println!("x bytes (little-endian):");
+        println!("x bytes ({}):", endianness);
         print!("  0x{:x}: ", x_ptr as usize);
         for i in 0..mem::size_of::<u32>() {
             let byte_ptr = (x_ptr as *const u8).add(i);
@@ -45,7 +171,7 @@ fn main() {
         }
         println!(" = 0x{:08x}", x);
         
-        println!("y bytes (little-endian):");
+        println!("y bytes ({}):", endianness);
         print!("  0x{:x}: ", y_ptr as usize);
         for i in 0..mem::size_of::<u32>() {
             let byte_ptr = (y_ptr as *const u8).add(i);
@@ -54,19 +180,24 @@ fn main() {
         println!(" = 0x{:08x}", y);
     }
     println!();
+    println!("Same bytes as a hexdump (x then y):");
+    let mut both = x.to_ne_bytes().to_vec();
+    both.extend_from_slice(&y.to_ne_bytes());
+    print!("{}", hexdump(&both));
+    println!();
     
     // METHOD 3: Cross-validate with reinterpretation
     println!("=== METHOD 3: REINTERPRETATION VALIDATION ===");
     
     // reinterpret the same bits as different types
     unsafe {
-        let x_as_two_u16s: [u16; 2] = mem::transmute(x);
+        let x_as_two_u16s: [u16; 2] = reinterpret(x);
         
         println!("Original x:     0x{:08x}", x);
         println!("Reinterpret as [u16;2]: [0x{:04x}, 0x{:04x}]", x_as_two_u16s[0], x_as_two_u16s[1]);
         
         // Validate: re-transform back
-        let x_restored: u32 = mem::transmute(x_as_two_u16s);
+        let x_restored: u32 = reinterpret(x_as_two_u16s);
         println!("Restored from [u16;2]: 0x{:08x} (valid: {})", x_restored, x_restored == x);
     }
     println!();
@@ -112,6 +243,36 @@ fn main() {
     mutable_x &= 0x00FF00FF;
     println!("After & 0x00FF00FF: 0x{:08x}", mutable_x);
     
+    println!();
+
+    // METHOD 6: Comparing bit patterns byte by byte
+    println!("=== METHOD 6: BIT DIFFERENCE (HAMMING DISTANCE) ===");
+
+    // Worked by hand, one hex digit (4 bits) at a time:
+    //   x       = 1 2 3 4 5 6 7 8
+    //   y       = 8 7 6 5 4 3 2 1
+    //   x ^ y   = 9 5 5 1 1 5 5 9
+    //   1-bits  = 2 2 2 1 1 2 2 2  → 14
+    let diff = bit_diff(&x, &y);
+    println!("x ^ y = 0x{:08x}", x ^ y);
+    println!("x and y differ in {} of {} bits (expected 14: {})", diff, u32::BITS, diff == 14);
+    println!("x bytes equal to itself: {}", bytes_equal(&x, &x));
+    println!("x bytes equal to y:      {}", bytes_equal(&x, &y));
+
+    println!();
+
+    // METHOD 7: Counting set bits
+    println!("=== METHOD 7: COUNTING SET BITS (POPCOUNT) ===");
+
+    // By hand, one hex digit at a time (the 8 on the right is bits 0-3):
+    //   digit   = 1    2    3    4    5    6    7    8
+    //   bits    = 0001 0010 0011 0100 0101 0110 0111 1000
+    //   1-bits  = 1    1    2    1    2    2    3    1     → 13
+    println!("0x{:08x} = {:032b}", x, x);
+    println!("Set bits (manual loop): {}", popcount(x));
+    println!("Set bits (count_ones):  {}", x.count_ones());
+    println!("Set at positions:       {:?}", bits_set_positions(x));
+
     println!();
     println!("=== FUNDAMENTAL TRUTH DEMONSTRATED ===");
     println!("Variables are just:");
@@ -131,3 +292,69 @@ fn main() {
     let mut input = String::new();
     std::io::stdin().read_line(&mut input).ok();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cases small enough to check in your head
+    #[test]
+    fn bit_diff_counts_the_bits_that_differ() {
+        assert_eq!(bit_diff(&0x12345678u32, &0x87654321u32), 14); // worked by hand in METHOD 6
+        assert_eq!(bit_diff(&0u8, &0xFFu8), 8); // every bit flipped
+        assert_eq!(bit_diff(&1u32, &2u32), 2); // 01 vs 10
+        assert_eq!(bit_diff(&7u16, &7u16), 0); // identical
+        assert_eq!(bit_diff(&[0u8, 1], &[1u8, 0]), 2); // works on arrays too
+    }
+
+    #[test]
+    fn bytes_equal_compares_memory_not_values() {
+        assert!(bytes_equal(&0x12345678u32, &0x12345678u32));
+        assert!(!bytes_equal(&0x12345678u32, &0x87654321u32));
+        assert!(!bytes_equal(&0.0f64, &-0.0f64)); // == says equal, the sign bit says otherwise
+    }
+
+    #[test]
+    fn popcount_matches_count_ones() {
+        let x = 0x12345678u32;
+        assert_eq!(popcount(x), 13); // worked by hand in METHOD 7
+        assert_eq!(popcount(x), x.count_ones());
+        assert_eq!(popcount(0), 0);
+        assert_eq!(popcount(u32::MAX), 32);
+    }
+
+    #[test]
+    fn bits_set_positions_lists_the_one_bits_lowest_first() {
+        assert_eq!(bits_set_positions(0x12345678), [3, 4, 5, 6, 9, 10, 12, 14, 18, 20, 21, 25, 28]);
+        assert_eq!(bits_set_positions(0b1010), [1, 3]); // 8 + 2
+        assert_eq!(bits_set_positions(1 << 31), [31]); // the top bit
+        assert!(bits_set_positions(0).is_empty());
+    }
+
+    #[test]
+    fn hexdump_prints_offset_hex_and_ascii() {
+        let dump = hexdump(&[0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(dump, format!("00000000: {:<47}  xV4.\n", "78 56 34 12"));
+    }
+
+    #[test]
+    fn hexdump_starts_a_new_row_every_16_bytes() {
+        let bytes: Vec<u8> = (b'A'..=b'R').collect(); // 18 bytes
+        let dump = hexdump(&bytes);
+        let rows: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            rows,
+            [
+                "00000000: 41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f 50  ABCDEFGHIJKLMNOP",
+                &format!("00000010: {:<47}  QR", "51 52"),
+            ]
+        );
+        assert_eq!(hexdump(&[]), "");
+    }
+
+    #[test]
+    fn host_endianness_matches_the_compile_target() {
+        let expected = if cfg!(target_endian = "little") { Endianness::Little } else { Endianness::Big };
+        assert_eq!(host_endianness(), expected);
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use crate::models::WeatherData;
+
+/// A limit that a city's weather crossed
+///
+/// Values are in whatever units the weather was fetched in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Alert {
+    TempAbove { city: String, temperature: f64, limit: f64 },
+    TempBelow { city: String, temperature: f64, limit: f64 },
+    WindAbove { city: String, wind_speed: f64, limit: f64 },
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alert::TempAbove { city, temperature, limit } => {
+                write!(f, "{}: temperature {} is above {}", city, temperature, limit)
+            }
+            Alert::TempBelow { city, temperature, limit } => {
+                write!(f, "{}: temperature {} is below {}", city, temperature, limit)
+            }
+            Alert::WindAbove { city, wind_speed, limit } => {
+                write!(f, "{}: wind speed {} is above {}", city, wind_speed, limit)
+            }
+        }
+    }
+}
+
+/// The limits set with --alert-temp-above, --alert-temp-below and --alert-wind-above
+///
+/// Limits are strict: a reading exactly at the limit does not trigger.
+/// ```
+/// # use weather_dashboard::alert::{Alert, Thresholds};
+/// # use weather_dashboard::models::Reading;
+/// # use weather_dashboard::WeatherData;
+/// let weather = |temperature, wind_speed| {
+///     WeatherData::new(Reading {
+///         location: "Phoenix, United States of America".to_string(),
+///         temperature,
+///         feels_like: temperature,
+///         humidity: 10,
+///         description: "Sunny".to_string(),
+///         wind_speed,
+///         wind_gust: Some(wind_speed),
+///         source: "WeatherAPI.com".to_string(),
+///         ..Reading::default()
+///     })
+///     .unwrap()
+/// };
+/// let limits = Thresholds { temp_above: Some(30.0), temp_below: Some(0.0), wind_above: Some(50.0) };
+///
+/// assert!(limits.check("Phoenix", &weather(30.0, 50.0)).is_empty());
+/// assert!(limits.check("Phoenix", &weather(0.0, 0.0)).is_empty());
+///
+/// let alerts = limits.check("Phoenix", &weather(30.1, 50.5));
+/// assert_eq!(alerts, [
+///     Alert::TempAbove { city: "Phoenix".to_string(), temperature: 30.1, limit: 30.0 },
+///     Alert::WindAbove { city: "Phoenix".to_string(), wind_speed: 50.5, limit: 50.0 },
+/// ]);
+///
+/// let alerts = limits.check("Phoenix", &weather(-0.1, 10.0));
+/// assert_eq!(alerts, [Alert::TempBelow { city: "Phoenix".to_string(), temperature: -0.1, limit: 0.0 }]);
+///
+/// assert!(Thresholds::default().check("Phoenix", &weather(99.0, 99.0)).is_empty());
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Thresholds {
+    pub temp_above: Option<f64>,
+    pub temp_below: Option<f64>,
+    pub wind_above: Option<f64>,
+}
+
+impl Thresholds {
+    /// True when at least one limit is set
+    pub fn any(&self) -> bool {
+        self.temp_above.is_some() || self.temp_below.is_some() || self.wind_above.is_some()
+    }
+
+    /// Every limit `weather` crosses, in the order temperature-above, temperature-below, wind
+    pub fn check(&self, city: &str, weather: &WeatherData) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        if let Some(limit) = self.temp_above
+            && weather.temperature > limit
+        {
+            alerts.push(Alert::TempAbove { city: city.to_string(), temperature: weather.temperature, limit });
+        }
+        if let Some(limit) = self.temp_below
+            && weather.temperature < limit
+        {
+            alerts.push(Alert::TempBelow { city: city.to_string(), temperature: weather.temperature, limit });
+        }
+        if let Some(limit) = self.wind_above
+            && weather.wind_speed > limit
+        {
+            alerts.push(Alert::WindAbove { city: city.to_string(), wind_speed: weather.wind_speed, limit });
+        }
+        alerts
+    }
+}
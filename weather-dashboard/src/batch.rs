@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use tokio::task::JoinSet;
+
+use crate::error::WeatherError;
+
+/// Runs `fetch` for every city concurrently, returning the results in the same order as `cities`
+///
+/// Every city gets an answer - a failure doesn't affect the others.
+/// ```
+/// # use weather_dashboard::batch::fetch_all;
+/// # use weather_dashboard::WeatherError;
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let cities = vec!["Paris".to_string(), "Atlantis".to_string(), "Oslo".to_string()];
+/// let results = fetch_all(&cities, |city| async move {
+///     if city == "Atlantis" { Err(WeatherError::CityNotFound(city)) } else { Ok(city.len()) }
+/// })
+/// .await;
+///
+/// assert_eq!(results.len(), 3);
+/// assert!(matches!(results[0], (_, Ok(5))));
+/// assert!(matches!(results[1], (_, Err(WeatherError::CityNotFound(_)))));
+/// assert!(matches!(results[2], (_, Ok(4))));
+/// # });
+/// ```
+pub async fn fetch_all<T, F, Fut>(cities: &[String], fetch: F) -> Vec<(String, Result<T, WeatherError>)>
+where
+    T: Send + 'static,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, WeatherError>> + Send + 'static,
+{
+    // Spawn one task per city - they all wait on the network at the same time
+    let handles: Vec<_> = cities.iter().map(|city| tokio::spawn(fetch(city.clone()))).collect();
+
+    let mut results = Vec::with_capacity(cities.len());
+    for (city, handle) in cities.iter().zip(handles) {
+        let result = match handle.await {
+            Ok(result) => result,
+            Err(e) => Err(WeatherError::ApiError(format!("fetch task failed: {}", e))),
+        };
+        results.push((city.clone(), result));
+    }
+    results
+}
+
+/// Like `fetch_all`, but the first failure to come back ends the whole batch
+///
+/// "First" means first to finish, not first in `cities`. The other fetches
+/// are aborted at that point, but any that were already in flight may well
+/// have reached the API - only their answers are thrown away. On success
+/// the results are in the same order as `cities`.
+/// ```
+/// # use weather_dashboard::batch::try_fetch_all;
+/// # use weather_dashboard::WeatherError;
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let cities = vec!["Paris".to_string(), "Atlantis".to_string(), "Oslo".to_string()];
+/// let fetch = |city: String| async move {
+///     if city == "Atlantis" { Err(WeatherError::CityNotFound(city)) } else { Ok(city.len()) }
+/// };
+///
+/// let (city, error) = try_fetch_all(&cities, fetch).await.unwrap_err();
+/// assert_eq!(city, "Atlantis");
+/// assert!(matches!(error, WeatherError::CityNotFound(_)));
+///
+/// let results = try_fetch_all(&cities[..1], fetch).await.unwrap();
+/// assert_eq!(results, [("Paris".to_string(), 5)]);
+/// # });
+/// ```
+pub async fn try_fetch_all<T, F, Fut>(cities: &[String], fetch: F) -> Result<Vec<(String, T)>, (String, WeatherError)>
+where
+    T: Send + 'static,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, WeatherError>> + Send + 'static,
+{
+    let mut tasks = JoinSet::new();
+    let mut index_of = HashMap::new();
+    for (index, city) in cities.iter().enumerate() {
+        let handle = tasks.spawn(fetch(city.clone()));
+        index_of.insert(handle.id(), index);
+    }
+
+    // Results arrive in completion order; the task id puts each back in place
+    let mut slots: Vec<Option<T>> = cities.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next_with_id().await {
+        // Returning early drops `tasks`, which aborts everything still running
+        match joined {
+            Ok((id, Ok(value))) => slots[index_of[&id]] = Some(value),
+            Ok((id, Err(e))) => return Err((cities[index_of[&id]].clone(), e)),
+            Err(e) => {
+                let error = WeatherError::ApiError(format!("fetch task failed: {}", e));
+                return Err((cities[index_of[&e.id()]].clone(), error));
+            }
+        }
+    }
+
+    Ok(cities.iter().cloned().zip(slots.into_iter().flatten()).collect())
+}
+
+/// `fetch_all`, or with `stop_on_first_error` `try_fetch_all`
+///
+/// Either way a success has one result per city in `cities` order; with
+/// `stop_on_first_error` the first failure comes back as the `Err` instead.
+pub async fn fetch_batch<T, F, Fut>(
+    cities: &[String],
+    stop_on_first_error: bool,
+    fetch: F,
+) -> Result<Vec<(String, Result<T, WeatherError>)>, (String, WeatherError)>
+where
+    T: Send + 'static,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, WeatherError>> + Send + 'static,
+{
+    if !stop_on_first_error {
+        return Ok(fetch_all(cities, fetch).await);
+    }
+    let results = try_fetch_all(cities, fetch).await?;
+    Ok(results.into_iter().map(|(city, value)| (city, Ok(value))).collect())
+}
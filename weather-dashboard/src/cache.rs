@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// How long fetched weather stays fresh
+pub const WEATHER_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// How long a city → coordinates lookup stays fresh (places don't move)
+pub const GEOCODE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// How long astronomy data is kept - entries are keyed by date, so a new
+/// calendar day is always a miss regardless of this
+pub const ASTRONOMY_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The form of a city name used in cache keys: trimmed, lowercased, single-spaced
+///
+/// Spellings the API treats as the same place share one entry instead of
+/// each costing a fetch. Reports still show the API's resolved name.
+/// ```
+/// # use weather_dashboard::cache::normalize_city;
+/// assert_eq!(normalize_city("London"), "london");
+/// assert_eq!(normalize_city("london"), "london");
+/// assert_eq!(normalize_city(" London "), "london");
+/// assert_eq!(normalize_city("New   York\t"), "new york");
+/// ```
+pub fn normalize_city(city: &str) -> String {
+    city.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Cache key for one city's astronomy on one date: `<city>-<YYYY-MM-DD>`
+pub fn astronomy_key(city: &str, date: &str) -> String {
+    format!("{}-{}", normalize_city(city), date)
+}
+
+/// One stored value plus when it was written (seconds since the Unix epoch)
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    stored_at: u64,
+    value: T,
+}
+
+/// A tiny persistent key → value cache stored as one JSON file
+///
+/// Entries older than `max_age` are treated as missing. Each cache gets its
+/// own file, so e.g. weather and geocode data can expire independently.
+pub struct Cache<T> {
+    path: PathBuf,
+    max_age: Duration,
+    _value: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> Cache<T> {
+    /// Creates a cache backed by the JSON file at `path` (created on first insert)
+    pub fn new(path: impl Into<PathBuf>, max_age: Duration) -> Self {
+        Cache {
+            path: path.into(),
+            max_age,
+            _value: PhantomData,
+        }
+    }
+
+    /// Returns the value for `key` if present and younger than `max_age`
+    pub fn get(&self, key: &str) -> Option<T> {
+        let mut entries = self.load();
+        let entry = entries.remove(key)?;
+        let age = now_secs().saturating_sub(entry.stored_at);
+        (age <= self.max_age.as_secs()).then_some(entry.value)
+    }
+
+    /// Returns the value for `key` however old it is, with how long ago it was stored
+    ///
+    /// For offline use, where stale data beats no data.
+    pub fn get_with_age(&self, key: &str) -> Option<(T, Duration)> {
+        let mut entries = self.load();
+        let entry = entries.remove(key)?;
+        let age = now_secs().saturating_sub(entry.stored_at);
+        Some((entry.value, Duration::from_secs(age)))
+    }
+
+    /// Stores `value` under `key`, replacing any older entry
+    pub fn insert(&self, key: &str, value: T) -> io::Result<()> {
+        let mut entries = self.load();
+        entries.insert(
+            key.to_string(),
+            Entry {
+                stored_at: now_secs(),
+                value,
+            },
+        );
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(&entries)?;
+        fs::write(&self.path, json)
+    }
+
+    /// Deletes the cache file; a cache that was never written is not an error
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Reads every entry - a missing or corrupt file just means an empty cache
+    fn load(&self) -> HashMap<String, Entry<T>> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Where the cache files live: `$XDG_CACHE_HOME`, then `~/.cache`, then the temp dir
+pub fn default_cache_dir() -> PathBuf {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("weather-dashboard")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -1,9 +1,81 @@
-use crate::{error::WeatherError, models::*};
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::{error::WeatherError, models::*, rate_limit::RateLimiter};
+
+/// Matches `key=<value>` (WeatherAPI) or `appid=<value>` (OpenWeatherMap)
+/// up to the next query separator, quote or whitespace
+///
+/// `\b` keeps it to whole parameter names: `monkey=` and `apikey=` are left alone.
+static API_KEY_PARAM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\b(key|appid)=[^&\s"']+"#).expect("valid regex"));
+
+/// Masks any `key=<value>` or `appid=<value>` in text so API keys never end up in logs
+///
+/// Some error responses echo the request URL back, key included.
+/// ```
+/// # use weather_dashboard::client::redact_api_key;
+/// assert_eq!(redact_api_key("?q=Oslo&appid=abc123&units=metric"), "?q=Oslo&appid=***&units=metric");
+/// ```
+pub fn redact_api_key(text: &str) -> String {
+    API_KEY_PARAM.replace_all(text, "$1=***").into_owned()
+}
+
+/// Where WeatherAPI.com requests go unless `WeatherClient::with_base_url` says otherwise
+pub const API_BASE_URL: &str = "https://api.weatherapi.com/v1";
+
+/// The oldest date `/history.json` has data for
+pub const HISTORY_EARLIEST: NaiveDate = match NaiveDate::from_ymd_opt(2010, 1, 1) {
+    Some(date) => date,
+    None => panic!("valid date"),
+};
+
+/// Rejects history dates after `today` or before `HISTORY_EARLIEST`
+///
+/// ```
+/// # use chrono::NaiveDate;
+/// # use weather_dashboard::client::check_history_date;
+/// let today = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+/// assert!(check_history_date(today, today).is_ok());
+/// assert!(check_history_date(NaiveDate::from_ymd_opt(2010, 1, 1).unwrap(), today).is_ok());
+///
+/// let tomorrow = check_history_date(NaiveDate::from_ymd_opt(2024, 6, 22).unwrap(), today);
+/// assert_eq!(tomorrow.unwrap_err().to_string(), "Invalid history date 2024-06-22: it is in the future");
+/// assert!(check_history_date(NaiveDate::from_ymd_opt(1999, 12, 31).unwrap(), today).is_err());
+/// ```
+pub fn check_history_date(date: NaiveDate, today: NaiveDate) -> Result<(), WeatherError> {
+    let problem = if date > today {
+        "it is in the future".to_string()
+    } else if date < HISTORY_EARLIEST {
+        format!("history only goes back to {}", HISTORY_EARLIEST)
+    } else {
+        return Ok(());
+    };
+    Err(WeatherError::InvalidDate(date.to_string(), problem))
+}
+
+/// Language codes WeatherAPI.com can translate condition text into
+///
+/// English is the default and has no code - leave the language unset for it.
+pub const SUPPORTED_LANGUAGES: [&str; 40] = [
+    "ar", "bn", "bg", "zh", "zh_tw", "cs", "da", "nl", "fi", "fr", "de", "el", "hi", "hu", "it", "ja", "jv",
+    "ko", "zh_cmn", "mr", "pl", "pt", "pa", "ro", "ru", "sr", "si", "sk", "es", "sv", "ta", "te", "tr", "uk",
+    "ur", "vi", "zh_wuu", "zh_hsn", "zh_yue", "zu",
+];
 
 /// HTTP client for fetching weather data
+///
+/// Cloning is cheap - the inner `reqwest::Client` shares its connection pool
+#[derive(Clone)]
 pub struct WeatherClient {
     client: reqwest::Client,
     api_key: String,
+    base_url: String,
+    rate_limiter: Option<RateLimiter>,
+    proxy: Option<String>,
+    lang: Option<String>,
 }
 
 impl WeatherClient {
@@ -12,74 +84,431 @@ impl WeatherClient {
         Self {
             client: reqwest::Client::new(),
             api_key,
+            base_url: API_BASE_URL.to_string(),
+            rate_limiter: None,
+            proxy: None,
+            lang: None,
+        }
+    }
+
+    /// Asks for condition text in `code`, one of `SUPPORTED_LANGUAGES` (e.g. "es", "fr", "de")
+    ///
+    /// Only the condition descriptions are translated; the numbers are unchanged.
+    pub fn with_lang(mut self, code: &str) -> Result<Self, WeatherError> {
+        let code = code.trim().to_ascii_lowercase();
+        if !SUPPORTED_LANGUAGES.contains(&code.as_str()) {
+            return Err(WeatherError::UnsupportedLanguage(code));
         }
+        self.lang = Some(code);
+        Ok(self)
+    }
+
+    /// Sends every request through the proxy at `url` (http, https or socks5)
+    ///
+    /// Without this, reqwest already honors the `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY`
+    /// environment variables; an explicit proxy set here overrides them.
+    pub fn with_proxy(mut self, url: &str) -> Result<Self, WeatherError> {
+        let proxy = reqwest::Proxy::all(url)
+            .map_err(|e| WeatherError::ProxyError(url.to_string(), e.to_string()))?;
+        self.client = reqwest::Client::builder().proxy(proxy).build()?;
+        self.proxy = Some(url.to_string());
+        Ok(self)
+    }
+
+    /// The proxy requests go through: the explicit one, else one from the environment
+    pub fn proxy(&self) -> Option<String> {
+        self.proxy.clone().or_else(|| {
+            ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy", "HTTP_PROXY", "http_proxy"]
+                .iter()
+                .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        })
+    }
+
+    /// Sends requests to `url` instead of `API_BASE_URL` - a mock server in tests
+    ///
+    /// ```
+    /// # use weather_dashboard::WeatherClient;
+    /// let client = WeatherClient::new("k".to_string()).with_base_url("http://127.0.0.1:1234/");
+    /// assert_eq!(client.build_url("Oslo"), "http://127.0.0.1:1234/current.json?key=k&q=Oslo&aqi=no");
+    /// ```
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Limits this client (and its clones) to `max_rps` requests per second
+    ///
+    /// Requests over the limit wait for a free slot rather than failing.
+    /// Must be called inside a tokio runtime.
+    pub fn with_max_rps(mut self, max_rps: u32) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(max_rps));
+        self
     }
 
     /// Fetches current weather for a city
     ///
     /// # Arguments
     /// * `city` - The city name to fetch weather for
-    /// * `units` - Units system: "metric" or "imperial"
+    /// * `units` - A `Units` system, or `UnitPrefs` to pick temperature and wind separately
     ///
     /// # Returns
     /// * `Result<WeatherData, WeatherError>` - Weather data or an error
     ///
     /// # Example
     /// ```no_run
+    /// # use weather_dashboard::{Units, WeatherClient};
+    /// # async fn run() -> Result<(), weather_dashboard::WeatherError> {
+    /// let client = WeatherClient::new("your_api_key".to_string());
+    /// let weather = client.fetch_weather("London", Units::Metric).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_weather(&self, city: &str, units: impl Into<UnitPrefs>) -> Result<WeatherData, WeatherError> {
+        let units = units.into();
+
+        // Make the HTTP request
+        let http_response = self.send(&self.build_url(city), city).await?;
+
+        // Parse the JSON response
+        let response = http_response
+            .json::<WeatherApiResponse>()
+            .await?;
+
+        // Convert API response to our WeatherData format
+        WeatherData::from_api_response(response, units)
+    }
+
+    /// The `/current.json` URL for `city`, API key included - what `fetch_weather` requests
+    ///
+    /// The response carries both metric and imperial values, so units don't
+    /// change the URL. Pass it through `redact_api_key` before showing it:
+    /// ```
+    /// # use weather_dashboard::WeatherClient;
+    /// # use weather_dashboard::client::redact_api_key;
+    /// let client = WeatherClient::new("secret123".to_string());
+    /// assert_eq!(
+    ///     redact_api_key(&client.build_url("London")),
+    ///     "https://api.weatherapi.com/v1/current.json?key=***&q=London&aqi=no"
+    /// );
+    ///
+    /// let client = client.with_lang("es").unwrap();
+    /// assert!(client.build_url("Madrid").ends_with("&q=Madrid&aqi=no&lang=es"));
+    ///
+    /// assert!(WeatherClient::new("secret123".to_string()).with_lang("xx").is_err());
+    /// ```
+    pub fn build_url(&self, city: &str) -> String {
+        format!(
+            "{}/current.json?key={}&q={}&aqi=no{}",
+            self.base_url,
+            self.api_key,
+            city,
+            self.lang_param()
+        )
+    }
+
+    /// `&lang=<code>` when a language was chosen, otherwise nothing
+    fn lang_param(&self) -> String {
+        self.lang.as_ref().map(|code| format!("&lang={}", code)).unwrap_or_default()
+    }
+
+    /// Fetches the current weather exactly as WeatherAPI.com returned it
+    ///
+    /// The body is not parsed, so this shows fields our models drop or would
+    /// reject. Non-success statuses still become errors.
+    pub async fn fetch_raw(&self, city: &str) -> Result<String, WeatherError> {
+        let http_response = self.send(&self.build_url(city), city).await?;
+        http_response.text().await.map_err(|e| self.network_error(e))
+    }
+
+    /// Fetches a daily forecast for a city
+    ///
+    /// # Arguments
+    /// * `city` - The city name to fetch the forecast for
+    /// * `days` - Number of days, 1-14 (the free tier stops at 3)
+    /// * `units` - Units system: `Units::Metric`, `Units::Imperial` or `Units::Kelvin`
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use weather_dashboard::{Units, WeatherClient};
+    /// # async fn run() -> Result<(), weather_dashboard::WeatherError> {
     /// let client = WeatherClient::new("your_api_key".to_string());
-    /// let weather = client.fetch_weather("London", "metric").await?;
+    /// let days = client.fetch_forecast("London", 3, Units::Metric).await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn fetch_weather(&self, city: &str, units: &str) -> Result<WeatherData, WeatherError> {
-        // Build the API URL for WeatherAPI.com
+    pub async fn fetch_forecast(&self, city: &str, days: u8, units: Units) -> Result<Vec<ForecastDay>, WeatherError> {
+        self.fetch_forecast_with_progress(city, days, units, |_, _| {}).await
+    }
+
+    /// Like `fetch_forecast`, reporting download progress as the body arrives
+    ///
+    /// `on_progress(received, total)` is called after every chunk with the bytes
+    /// read so far and the `Content-Length`, if the server sent one. Long
+    /// forecasts are large enough to notice on a slow link.
+    pub async fn fetch_forecast_with_progress<F>(
+        &self,
+        city: &str,
+        days: u8,
+        units: Units,
+        mut on_progress: F,
+    ) -> Result<Vec<ForecastDay>, WeatherError>
+    where
+        F: FnMut(u64, Option<u64>),
+    {
         let url = format!(
-            "https://api.weatherapi.com/v1/current.json?key={}&q={}&aqi=no",
-            self.api_key, city
+            "{}/forecast.json?key={}&q={}&days={}&aqi=no&alerts=no{}",
+            self.base_url,
+            self.api_key,
+            city,
+            days,
+            self.lang_param()
         );
 
-        // Make the HTTP request
+        let mut http_response = self.send(&url, city).await?;
+        let total = http_response.content_length();
+        let mut body = Vec::with_capacity(total.unwrap_or(0) as usize);
+        on_progress(0, total);
+        while let Some(chunk) = http_response.chunk().await.map_err(|e| self.network_error(e))? {
+            body.extend_from_slice(&chunk);
+            on_progress(body.len() as u64, total);
+        }
+        let response: ForecastApiResponse = serde_json::from_slice(&body)?;
+
+        let forecast = response
+            .forecast
+            .forecastday
+            .into_iter()
+            .map(|day| ForecastDay::from_api_day(day, units))
+            .collect();
+
+        Ok(forecast)
+    }
+
+    /// Fetches what the weather was in a city on a past `date`
+    ///
+    /// `/history.json` needs a paid plan; on the free tier this fails with an
+    /// API error. The date is checked with `check_history_date` before any request.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use weather_dashboard::{Units, WeatherClient};
+    /// # async fn run() -> Result<(), weather_dashboard::WeatherError> {
+    /// let client = WeatherClient::new("your_api_key".to_string());
+    /// let date = chrono::NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+    /// let day = client.fetch_history("London", date, Units::Metric).await?;
+    /// println!("High: {}", day.max_temp);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_history(&self, city: &str, date: NaiveDate, units: Units) -> Result<ForecastDay, WeatherError> {
+        check_history_date(date, chrono::Local::now().date_naive())?;
+
+        let http_response = self.send(&self.history_url(city, date), city).await?;
+        let response = http_response.json::<ForecastApiResponse>().await?;
+        let day = response
+            .forecast
+            .forecastday
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeatherError::ApiError(format!("no history for {} on {}", city, date)))?;
+        Ok(ForecastDay::from_api_day(day, units))
+    }
+
+    /// The `/history.json` URL for `city` on `date`, API key included
+    ///
+    /// ```
+    /// # use weather_dashboard::WeatherClient;
+    /// # use weather_dashboard::client::redact_api_key;
+    /// let client = WeatherClient::new("secret123".to_string());
+    /// let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+    /// assert_eq!(
+    ///     redact_api_key(&client.history_url("London", date)),
+    ///     "https://api.weatherapi.com/v1/history.json?key=***&q=London&dt=2024-03-05"
+    /// );
+    /// ```
+    pub fn history_url(&self, city: &str, date: NaiveDate) -> String {
+        format!(
+            "{}/history.json?key={}&q={}&dt={}{}",
+            self.base_url,
+            self.api_key,
+            city,
+            date.format("%Y-%m-%d"),
+            self.lang_param()
+        )
+    }
+
+    /// Fetches sunrise, sunset, moonrise, moonset and moon phase for `date` (YYYY-MM-DD)
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use weather_dashboard::WeatherClient;
+    /// # async fn run() -> Result<(), weather_dashboard::WeatherError> {
+    /// let client = WeatherClient::new("your_api_key".to_string());
+    /// let astro = client.fetch_astronomy("London", "2024-06-21").await?;
+    /// println!("Sunset: {}", astro.sunset);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_astronomy(&self, city: &str, date: &str) -> Result<Astronomy, WeatherError> {
+        let url = format!(
+            "{}/astronomy.json?key={}&q={}&dt={}",
+            self.base_url, self.api_key, city, date
+        );
+
+        let http_response = self.send(&url, city).await?;
+        let response = http_response.json::<AstronomyApiResponse>().await?;
+        Ok(response.astronomy.astro)
+    }
+
+    /// Searches for locations matching `query`, for disambiguating names like "Springfield"
+    ///
+    /// Each suggestion carries coordinates that can be passed back as the city
+    /// (`"lat,lon"`) to fetch weather for that exact place.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use weather_dashboard::WeatherClient;
+    /// # async fn run() -> Result<(), weather_dashboard::WeatherError> {
+    /// let client = WeatherClient::new("your_api_key".to_string());
+    /// let matches = client.search_cities("Springfield").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_cities(&self, query: &str) -> Result<Vec<CitySuggestion>, WeatherError> {
+        let url = format!(
+            "{}/search.json?key={}&q={}",
+            self.base_url, self.api_key, query
+        );
+
+        let http_response = self.send(&url, query).await?;
+        Ok(http_response.json::<Vec<CitySuggestion>>().await?)
+    }
+
+    /// Resolves a city name to coordinates using the best `/search.json` match
+    ///
+    /// Returns `WeatherError::CityNotFound` when nothing matches.
+    pub async fn geocode(&self, city: &str) -> Result<GeoLocation, WeatherError> {
+        let best = self
+            .search_cities(city)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeatherError::CityNotFound(city.to_string()))?;
+
+        Ok(GeoLocation {
+            lat: best.lat,
+            lon: best.lon,
+            resolved_name: format!("{}, {}, {}", best.name, best.region, best.country),
+        })
+    }
+
+    /// Wraps a request failure, calling out the proxy when the connection itself failed
+    fn network_error(&self, e: reqwest::Error) -> WeatherError {
+        // reqwest errors print the request URL - drop it so the key isn't shown
+        let e = e.without_url();
+        match self.proxy() {
+            Some(proxy) if e.is_connect() => WeatherError::ProxyError(proxy, e.to_string()),
+            _ => WeatherError::NetworkError(e),
+        }
+    }
+
+    /// Sends a GET request and turns non-success statuses into a `WeatherError`
+    async fn send(&self, url: &str, city: &str) -> Result<reqwest::Response, WeatherError> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+
         let http_response = self
             .client
-            .get(&url)
+            .get(url)
             .send()
-            .await?; // Network request (can fail)
+            .await
+            .map_err(|e| self.network_error(e))?; // Network request (can fail)
 
         // Check if the request was successful
         if !http_response.status().is_success() {
             let status = http_response.status();
-            let error_text = http_response.text().await?;
+
+            // 429 Too Many Requests - pass on how long the API wants us to back off
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                let retry_after = http_response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse::<u64>().ok());
+                return Err(WeatherError::RateLimited(retry_after));
+            }
+
+            let error_text = redact_api_key(&http_response.text().await?);
+
+            // WeatherAPI answers unknown locations with 400 + error code 1006
+            if status == reqwest::StatusCode::BAD_REQUEST && error_text.contains("1006") {
+                return Err(WeatherError::CityNotFound(city.to_string()));
+            }
+
             return Err(WeatherError::ApiError(format!(
                 "API returned status {}: {}",
                 status, error_text
             )));
         }
 
-        // Parse the JSON response
-        let response = http_response
-            .json::<WeatherApiResponse>()
-            .await?;
+        Ok(http_response)
+    }
+}
 
-        // Convert API response to our WeatherData format
-        // Choose temperature and wind speed based on units
-        let (temperature, feels_like, wind_speed) = match units {
-            "imperial" => (
-                response.current.temp_f,
-                response.current.feelslike_f,
-                response.current.wind_mph,
-            ),
-            _ => (
-                response.current.temp_c,
-                response.current.feelslike_c,
-                response.current.wind_kph,
-            ),
-        };
-
-        Ok(WeatherData {
-            temperature,
-            feels_like,
-            humidity: response.current.humidity,
-            description: response.current.condition.text,
-            wind_speed,
-            source: format!("WeatherAPI.com - {}, {}", response.location.name, response.location.country),
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_masks_the_weatherapi_key() {
+        assert_eq!(
+            redact_api_key("https://api.weatherapi.com/v1/current.json?key=secret123&q=Oslo"),
+            "https://api.weatherapi.com/v1/current.json?key=***&q=Oslo"
+        );
+        assert_eq!(redact_api_key("key=secret123"), "key=***");
+        assert_eq!(redact_api_key(r#"{"url": "/v1?key=secret123"}"#), r#"{"url": "/v1?key=***"}"#);
+    }
+
+    #[test]
+    fn redact_leaves_longer_names_ending_in_key_alone() {
+        assert_eq!(redact_api_key("?monkey=banana&apikey=x"), "?monkey=banana&apikey=x");
+        assert_eq!(redact_api_key("?monkey=banana&key=secret"), "?monkey=banana&key=***");
+    }
+
+    #[tokio::test]
+    async fn fetch_raw_returns_the_body_exactly_as_sent() {
+        // Odd spacing, a field our models don't have and a non-ASCII name: none of it may change
+        let body = "{\"location\": {\"name\":  \"Zürich\"},\n  \"extra_field\": [1, 2]}\n";
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/current.json")
+            .match_query(mockito::Matcher::UrlEncoded("q".into(), "Zurich".into()))
+            .with_status(200)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let raw = client.fetch_raw("Zurich").await.unwrap();
+
+        assert_eq!(raw.as_bytes(), body.as_bytes());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn fetch_raw_still_turns_error_statuses_into_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/current.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .with_body(r#"{"error": {"code": 1006, "message": "No matching location found."}}"#)
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let error = client.fetch_raw("Atlantis").await.unwrap_err();
+        assert!(matches!(error, WeatherError::CityNotFound(city) if city == "Atlantis"));
     }
 }
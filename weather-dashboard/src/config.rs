@@ -0,0 +1,65 @@
+use crate::error::WeatherError;
+
+/// Name of the environment variable holding the WeatherAPI.com key
+pub const API_KEY_VAR: &str = "WEATHER_API_KEY";
+
+/// Reads the API key from the environment (a `.env` file is loaded into it first)
+///
+/// Returns `WeatherError::MissingApiKey` when the variable is unset or blank.
+pub fn load_api_key() -> Result<String, WeatherError> {
+    api_key_from(std::env::var(API_KEY_VAR).ok())
+}
+
+/// Validates a raw key value - split out from `load_api_key` so it doesn't depend on the environment
+pub fn api_key_from(value: Option<String>) -> Result<String, WeatherError> {
+    match value {
+        Some(key) if !key.trim().is_empty() => Ok(key.trim().to_string()),
+        _ => Err(WeatherError::MissingApiKey),
+    }
+}
+
+/// Shows just enough of a secret to tell which one it is: the first and last two characters
+///
+/// Anything of 4 characters or fewer is masked entirely, since showing four
+/// characters of it would show all of it.
+/// ```
+/// # use weather_dashboard::config::mask_secret;
+/// assert_eq!(mask_secret("ab1234yz"), "ab****yz");
+/// assert_eq!(mask_secret("abc"), "***");
+/// assert_eq!(mask_secret(""), "");
+/// ```
+pub fn mask_secret(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= 4 {
+        return "*".repeat(chars.len());
+    }
+    let (head, tail) = (&chars[..2], &chars[chars.len() - 2..]);
+    format!(
+        "{}{}{}",
+        head.iter().collect::<String>(),
+        "*".repeat(chars.len() - 4),
+        tail.iter().collect::<String>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_unset_key_is_missing() {
+        assert!(matches!(api_key_from(None), Err(WeatherError::MissingApiKey)));
+    }
+
+    #[test]
+    fn a_blank_key_is_missing() {
+        assert!(matches!(api_key_from(Some(String::new())), Err(WeatherError::MissingApiKey)));
+        assert!(matches!(api_key_from(Some(" \t\n".to_string())), Err(WeatherError::MissingApiKey)));
+    }
+
+    #[test]
+    fn a_valid_key_is_trimmed() {
+        assert_eq!(api_key_from(Some("abc123".to_string())).unwrap(), "abc123");
+        assert_eq!(api_key_from(Some("  abc123\n".to_string())).unwrap(), "abc123");
+    }
+}
@@ -0,0 +1,698 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use colored::{Color, ColoredString, Colorize};
+use thiserror::Error;
+
+use crate::models::{Astronomy, ForecastDay, ReportField, UnitPrefs, Units, WeatherData};
+
+/// Rain chance (%) above which a forecast row is highlighted
+pub const HIGH_RAIN_CHANCE: u8 = 70;
+
+/// Decimal places for temperatures and wind unless `--digits` says otherwise
+pub const DEFAULT_DIGITS: usize = 1;
+
+/// Temperatures below this (°C) are shown in blue
+pub const FREEZING_C: f64 = 0.0;
+
+/// Temperatures above this (°C) are shown in red
+pub const HOT_C: f64 = 30.0;
+
+/// Wind above this (km/h) is shown in red
+pub const HIGH_WIND_KPH: f64 = 40.0;
+
+/// When to use terminal colors: `--color auto|always|never`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and `NO_COLOR` isn't set
+    #[default]
+    Auto,
+    /// Even when piped, e.g. into `less -R`
+    Always,
+    /// Never
+    Never,
+}
+
+/// A color choice that `ColorChoice::from_str` doesn't recognise
+#[derive(Debug, Error)]
+#[error("unknown color choice '{0}' (expected auto, always or never)")]
+pub struct UnknownColorChoice(String);
+
+impl FromStr for ColorChoice {
+    type Err = UnknownColorChoice;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(UnknownColorChoice(s.to_string())),
+        }
+    }
+}
+
+/// The canonical lowercase name, which `from_str` reads back
+impl fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        })
+    }
+}
+
+impl ColorChoice {
+    /// Whether to color, given whether stdout is a terminal and whether `NO_COLOR` is set
+    ///
+    /// `NO_COLOR` (<https://no-color.org>) turns `auto` off; only an explicit
+    /// `always` overrides it.
+    /// ```
+    /// # use weather_dashboard::display::ColorChoice;
+    /// //                                   (tty,   NO_COLOR)
+    /// assert!(ColorChoice::Auto.enabled(true, false));
+    /// assert!(!ColorChoice::Auto.enabled(false, false));
+    /// assert!(!ColorChoice::Auto.enabled(true, true));
+    ///
+    /// assert!(ColorChoice::Always.enabled(false, false));
+    /// assert!(ColorChoice::Always.enabled(false, true));
+    ///
+    /// assert!(!ColorChoice::Never.enabled(true, false));
+    /// ```
+    pub fn enabled(self, stdout_is_tty: bool, no_color_env: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => stdout_is_tty && !no_color_env,
+        }
+    }
+}
+
+/// Width of the label column in an aligned (`--format table`) report - fits "Temperature:"
+pub const LABEL_WIDTH: usize = 12;
+
+/// Pads `label` with spaces to `width` characters, so the values after it line up
+///
+/// Counts characters, not bytes, and never truncates a label that's too long.
+/// ```
+/// # use weather_dashboard::display::pad_label;
+/// let rows = [pad_label("City:", 12) + " Paris", pad_label("Temperature:", 12) + " 21.0°C"];
+/// assert_eq!(rows[0], "City:        Paris");
+/// assert_eq!(rows[1], "Temperature: 21.0°C");
+/// assert_eq!(rows[0].find("Paris"), rows[1].find("21.0"));
+///
+/// assert_eq!(pad_label("Température:", 13).chars().count(), 13);
+/// assert_eq!(pad_label("Much too long:", 4), "Much too long:");
+/// ```
+pub fn pad_label(label: &str, width: usize) -> String {
+    format!("{:<width$}", label, width = width)
+}
+
+/// The color for a temperature: blue below freezing, red when hot, yellow in between
+///
+/// ```
+/// # use colored::Color;
+/// # use weather_dashboard::display::temp_color;
+/// assert_eq!(temp_color(-0.1), Color::Blue);
+/// assert_eq!(temp_color(0.0), Color::Yellow);
+/// assert_eq!(temp_color(30.0), Color::Yellow);
+/// assert_eq!(temp_color(30.1), Color::Red);
+/// ```
+pub fn temp_color(temp_c: f64) -> Color {
+    if temp_c < FREEZING_C {
+        Color::Blue
+    } else if temp_c > HOT_C {
+        Color::Red
+    } else {
+        Color::Yellow
+    }
+}
+
+/// The color for a wind speed: red above `HIGH_WIND_KPH`, green otherwise
+///
+/// ```
+/// # use colored::Color;
+/// # use weather_dashboard::display::wind_color;
+/// assert_eq!(wind_color(40.0), Color::Green);
+/// assert_eq!(wind_color(40.1), Color::Red);
+/// ```
+pub fn wind_color(wind_kph: f64) -> Color {
+    if wind_kph > HIGH_WIND_KPH { Color::Red } else { Color::Green }
+}
+
+/// Renders the human-readable weather report
+///
+/// `units` must match what the data was fetched with - it only picks the labels.
+/// With `colorize` off the output is plain text, suitable for files and tests.
+///
+/// # Example
+/// ```no_run
+/// # use weather_dashboard::{render_report, Units, WeatherData};
+/// # fn show(weather: &WeatherData) {
+/// let report = render_report(weather, Units::Metric, false);
+/// assert!(report.contains("°C"));
+/// # }
+/// ```
+pub fn render_report(data: &WeatherData, units: impl Into<UnitPrefs>, colorize: bool) -> String {
+    ReportView::new(data, units).colorized(colorize).to_string()
+}
+
+/// How much the temperature moved since the previous cached reading, for `--temp-trend`
+///
+/// `delta` is in the report's own units (current minus previous).
+/// ```
+/// # use std::time::Duration;
+/// # use weather_dashboard::cache::Cache;
+/// # use weather_dashboard::display::TempTrend;
+/// # use weather_dashboard::models::Reading;
+/// # use weather_dashboard::{ReportView, Units, WeatherData};
+/// let reading = |temperature| {
+///     WeatherData::new(Reading {
+///         location: "Oslo, Norway".to_string(),
+///         temperature,
+///         feels_like: temperature,
+///         humidity: 70,
+///         description: "Cloudy".to_string(),
+///         wind_speed: 10.0,
+///         wind_gust: None,
+///         source: "WeatherAPI.com".to_string(),
+///         ..Reading::default()
+///     })
+///     .unwrap()
+/// };
+/// let path = std::env::temp_dir().join(format!("weather-trend-doctest-{}.json", std::process::id()));
+/// let cache: Cache<WeatherData> = Cache::new(&path, Duration::from_secs(600));
+/// cache.insert("oslo", reading(10.0)).unwrap();
+///
+/// // A new reading comes in: compare it with what the cache held before replacing it
+/// let (previous, _age) = cache.get_with_age("oslo").unwrap();
+/// let current = reading(12.0);
+/// let trend = TempTrend::between(previous.temperature, current.temperature);
+/// assert_eq!((trend.arrow(), trend.delta), ("↑", 2.0));
+///
+/// let report = ReportView::new(&current, Units::Metric).trend(Some(trend)).to_string();
+/// assert!(report.contains("12.0°C ↑ (+2.0° since last)"));
+///
+/// assert_eq!(TempTrend::between(12.0, 9.5).arrow(), "↓");
+/// assert_eq!(TempTrend::between(12.0, 12.01).arrow(), "→");
+/// # cache.clear().unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TempTrend {
+    pub delta: f64,
+}
+
+impl TempTrend {
+    /// Changes smaller than this (either way) count as flat
+    pub const FLAT: f64 = 0.05;
+
+    /// The change from `previous` to `current`
+    pub fn between(previous: f64, current: f64) -> Self {
+        TempTrend { delta: current - previous }
+    }
+
+    /// "↑" warmer, "↓" colder, "→" about the same
+    pub fn arrow(&self) -> &'static str {
+        if self.delta >= Self::FLAT {
+            "↑"
+        } else if self.delta <= -Self::FLAT {
+            "↓"
+        } else {
+            "→"
+        }
+    }
+}
+
+/// A `WeatherData` paired with the units it was fetched in, ready to print
+///
+/// `Display` can't take extra arguments, so this wrapper carries the unit
+/// labels (and whether to colorize) alongside the data. It owns the report
+/// layout - `render_report` and `WeatherData`'s own `Display` both use it.
+pub struct ReportView<'a> {
+    data: &'a WeatherData,
+    units: Option<UnitPrefs>,
+    colorize: bool,
+    digits: Option<usize>,
+    aligned: bool,
+    trend: Option<TempTrend>,
+}
+
+impl<'a> ReportView<'a> {
+    /// A plain-text view labelled with `units`
+    pub fn new(data: &'a WeatherData, units: impl Into<UnitPrefs>) -> Self {
+        ReportView {
+            data,
+            units: Some(units.into()),
+            colorize: false,
+            digits: Some(DEFAULT_DIGITS),
+            aligned: false,
+            trend: None,
+        }
+    }
+
+    /// Turns terminal colors on or off
+    pub fn colorized(mut self, colorize: bool) -> Self {
+        self.colorize = colorize;
+        self
+    }
+
+    /// Rounds temperatures and wind to `digits` decimal places
+    pub fn digits(mut self, digits: usize) -> Self {
+        self.digits = Some(digits);
+        self
+    }
+
+    /// Pads every label to `LABEL_WIDTH`, so the values form one column
+    pub fn aligned(mut self, aligned: bool) -> Self {
+        self.aligned = aligned;
+        self
+    }
+
+    /// Shows an arrow and the change since the last reading after the temperature
+    pub fn trend(mut self, trend: Option<TempTrend>) -> Self {
+        self.trend = trend;
+        self
+    }
+}
+
+impl fmt::Display for ReportView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paint = |text: ColoredString| paint(text, self.colorize);
+        // Padded before painting - escape codes would count towards the width
+        let label = |name: &str| {
+            let name = format!("{}:", name);
+            paint(if self.aligned { pad_label(&name, LABEL_WIDTH) } else { name }.bold())
+        };
+        let data = self.data;
+        let number = |value: f64| match self.digits {
+            Some(digits) => format!("{:.*}", digits, value),
+            None => value.to_string(),
+        };
+        let (temp_unit, wind_unit) = match self.units {
+            Some(units) => (units.temp.temp_label(), format!(" {}", units.wind.wind_label())),
+            None => ("", String::new()),
+        };
+        // Colors are picked in °C and km/h; unlabelled data is taken as metric
+        let prefs = self.units.unwrap_or_default();
+        let temp_color = |temp: f64| temp_color(prefs.temp.to_celsius(temp));
+        let wind_color = |speed: f64| wind_color(prefs.wind.to_kph(speed));
+        // A difference in kelvin is written "3 K", in Celsius or Fahrenheit "3°"
+        let degrees = match self.units {
+            Some(units) if units.temp == Units::Kelvin => " K",
+            _ => "°",
+        };
+
+        writeln!(f, "\n{}", paint("Weather Report".bold().underline()))?;
+        writeln!(f, "{} {}", label("City"), data.location)?;
+        write!(f, "{} {}{}", label("Temperature"), paint(number(data.temperature).color(temp_color(data.temperature))), temp_unit)?;
+        if let Some(trend) = self.trend {
+            let delta = match self.digits {
+                Some(digits) => format!("{:+.*}", digits, trend.delta),
+                None => format!("{:+}", trend.delta),
+            };
+            write!(f, " {} ({}{} since last)", trend.arrow(), delta, degrees)?;
+        }
+        writeln!(f)?;
+        write!(f, "{} {}{}", label("Feels like"), paint(number(data.feels_like).color(temp_color(data.feels_like))), temp_unit)?;
+        let delta = data.feels_like_delta().round();
+        if delta != 0.0 {
+            let direction = if delta < 0.0 { "colder" } else { "warmer" };
+            write!(f, " ({}{} {})", delta.abs(), degrees, direction)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{} {}", label("Humidity"), paint(data.humidity.to_string().blue()))?;
+        writeln!(f, "{} {}", label("Conditions"), data.description)?;
+        write!(f, "{} {}{}", label("Wind speed"), paint(number(data.wind_speed).color(wind_color(data.wind_speed))), wind_unit)?;
+        match data.wind_gust {
+            Some(gust) if gust > data.wind_speed => {
+                write!(f, ", gusting to {}{}", paint(number(gust).color(wind_color(gust))), wind_unit)?;
+            }
+            Some(_) => {}
+            // Not reported by the provider, or cached from before gusts were tracked
+            None => write!(f, ", gusts n/a")?,
+        }
+        writeln!(f)?;
+        if !data.observed_at.is_empty() {
+            writeln!(f, "{} {} local", label("Observed"), data.observed_at)?;
+        }
+        writeln!(f, "{} {}", label("Source"), paint(data.source.dimmed()))
+    }
+}
+
+/// The plain report without unit labels - use `ReportView` to include them
+impl fmt::Display for WeatherData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ReportView {
+            data: self,
+            units: None,
+            colorize: false,
+            digits: None,
+            aligned: false,
+            trend: None,
+        }
+        .fmt(f)
+    }
+}
+
+/// Renders a multi-day forecast as a table, one row per day
+///
+/// Rows where the chance of rain exceeds `HIGH_RAIN_CHANCE` are highlighted -
+/// bold with a ☔ marker, or a `*` marker when `emoji` is off.
+pub fn render_forecast(location: &str, days: &[ForecastDay], units: Units, colorize: bool, emoji: bool) -> String {
+    render_days(&format!("Forecast for {}", location), days, units, colorize, emoji)
+}
+
+/// Renders a past day from `/history.json` - the same row as a forecast day
+pub fn render_history(location: &str, day: &ForecastDay, units: Units, colorize: bool, emoji: bool) -> String {
+    render_days(&format!("Weather history for {}", location), std::slice::from_ref(day), units, colorize, emoji)
+}
+
+/// The day table shared by forecasts and history, under `heading`
+fn render_days(heading: &str, days: &[ForecastDay], units: Units, colorize: bool, emoji: bool) -> String {
+    let temp_unit = units.temp_label();
+
+    let mut table = String::new();
+    table.push_str(&format!("\n{}\n", paint(heading.bold().underline(), colorize)));
+    table.push_str(&format!(
+        "{:<10}  {:<4}  {:<26}  {:>15}  {:>5}  {}\n",
+        "Date", "", "Conditions", "Min / Max", "Rain", "Moon"
+    ));
+
+    for day in days {
+        let icon = if emoji { condition_icon(day.condition_code) } else { "" };
+        let moon = if emoji { moon_icon(&day.moon_phase) } else { day.moon_phase.as_str() };
+        let temps = format!("{:.1} / {:.1}{}", day.min_temp, day.max_temp, temp_unit);
+        let wet = *day.chance_of_rain > HIGH_RAIN_CHANCE;
+        let marker = match (wet, emoji) {
+            (true, true) => " ☔",
+            (true, false) => " *",
+            (false, _) => "",
+        };
+
+        let row = format!(
+            "{:<10}  {:<4}  {:<26}  {:>15}  {:>5}  {}{}",
+            day.date, icon, day.description, temps, day.chance_of_rain, moon, marker
+        );
+        let row = if wet { paint(row.bold(), colorize) } else { row };
+        table.push_str(&row);
+        table.push('\n');
+    }
+
+    table
+}
+
+/// Renders sun and moon times for one location
+pub fn render_astronomy(location: &str, date: &str, astro: &Astronomy, colorize: bool) -> String {
+    let heading = format!("Astronomy for {} on {}", location, date);
+    let mut out = format!("\n{}\n", paint(heading.bold().underline(), colorize));
+    for (label, value) in [
+        ("Sunrise", &astro.sunrise),
+        ("Sunset", &astro.sunset),
+        ("Moonrise", &astro.moonrise),
+        ("Moonset", &astro.moonset),
+        ("Moon phase", &astro.moon_phase),
+    ] {
+        out.push_str(&format!("{}: {}\n", paint(label.bold(), colorize), value));
+    }
+    out
+}
+
+/// Renders two cities side by side: temperature, feels-like, humidity and wind
+///
+/// A `*` marks the warmer city and the less humid one. A city whose fetch
+/// failed is passed as `None` and shows `N/A` in every row. Temperatures and
+/// wind are rounded to `digits` decimal places.
+/// ```
+/// # use weather_dashboard::display::render_comparison;
+/// # use weather_dashboard::models::Reading;
+/// # use weather_dashboard::{Units, WeatherData};
+/// let lima = WeatherData::new(Reading { temperature: 12.75, humidity: 80, wind_speed: 9.0, ..Reading::default() }).unwrap();
+/// let table = render_comparison(("Lima", Some(&lima)), ("Atlantis", None), Units::Metric, 2, false);
+/// let temperature = table.lines().find(|line| line.starts_with("Temperature")).unwrap();
+/// assert_eq!(temperature.split_whitespace().collect::<Vec<_>>(), ["Temperature", "12.75°C", "N/A"]);
+/// assert!(table.contains("9.00 km/h"));
+/// ```
+pub fn render_comparison(
+    left: (&str, Option<&WeatherData>),
+    right: (&str, Option<&WeatherData>),
+    units: impl Into<UnitPrefs>,
+    digits: usize,
+    colorize: bool,
+) -> String {
+    let units = units.into();
+    let (temp_unit, wind_unit) = (units.temp.temp_label(), units.wind.wind_label());
+    let warmer = winner(left.1, right.1, |w| w.temperature, true);
+    let drier = winner(left.1, right.1, |w| f64::from(*w.humidity), false);
+
+    let mut table = String::new();
+    table.push_str(&format!("\n{}\n", paint("Weather Comparison".bold().underline(), colorize)));
+    table.push_str(&format!("{:<12}  {:>20}    {:>20}\n", "", left.0, right.0));
+
+    let mut row = |label: &str, value: &dyn Fn(&WeatherData) -> String, marked: Option<Side>| {
+        // The marker gets its own two columns so values stay right-aligned
+        let cell = |data: Option<&WeatherData>, side: Side| {
+            let marker = if data.is_some() && marked == Some(side) { " *" } else { "  " };
+            let value = data.map_or_else(|| "N/A".to_string(), value);
+            format!("{:>20}{}", value, marker)
+        };
+        let line = format!(
+            "{}  {}  {}",
+            paint(format!("{:<12}", label).bold(), colorize),
+            cell(left.1, Side::Left),
+            cell(right.1, Side::Right)
+        );
+        table.push_str(line.trim_end());
+        table.push('\n');
+    };
+    row("Temperature", &|w| format!("{:.*}{}", digits, w.temperature, temp_unit), warmer);
+    row("Feels like", &|w| format!("{:.*}{}", digits, w.feels_like, temp_unit), None);
+    row("Humidity", &|w| w.humidity.to_string(), drier);
+    row("Wind speed", &|w| format!("{:.*} {}", digits, w.wind_speed, wind_unit), None);
+
+    table
+}
+
+/// Which column of a comparison a value sits in
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// The side with the higher (or lower) value - `None` on a tie or when either side is missing
+fn winner(a: Option<&WeatherData>, b: Option<&WeatherData>, key: fn(&WeatherData) -> f64, higher: bool) -> Option<Side> {
+    let (a, b) = (key(a?), key(b?));
+    if a == b {
+        None
+    } else if (a > b) == higher {
+        Some(Side::Left)
+    } else {
+        Some(Side::Right)
+    }
+}
+
+/// Describes how long ago something happened: "just now", "5 minutes ago", "3 hours ago"
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (count, unit) = match secs {
+        0..60 => return "just now".to_string(),
+        60..3600 => (secs / 60, "minute"),
+        3600..86400 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
+}
+
+/// Picks an emoji for a WeatherAPI.com condition code
+pub fn condition_icon(code: u32) -> &'static str {
+    match code {
+        1000 => "☀️",
+        1003 => "⛅",
+        1006 | 1009 => "☁️",
+        1030 | 1135 | 1147 => "🌫️",
+        1087 | 1273..=1282 => "⛈️",
+        1066 | 1069 | 1072 | 1114 | 1117 | 1204..=1237 | 1249..=1264 => "❄️",
+        _ => "🌧️",
+    }
+}
+
+/// Picks an emoji for WeatherAPI.com's moon phase names, falling back to the name itself
+pub fn moon_icon(phase: &str) -> &str {
+    match phase {
+        "New Moon" => "🌑",
+        "Waxing Crescent" => "🌒",
+        "First Quarter" => "🌓",
+        "Waxing Gibbous" => "🌔",
+        "Full Moon" => "🌕",
+        "Waning Gibbous" => "🌖",
+        "Last Quarter" => "🌗",
+        "Waning Crescent" => "🌘",
+        other => other,
+    }
+}
+
+/// Just one value of a reading, for `--field`: no label, no color
+///
+/// Numbers are rounded to `digits` places; `with_unit` appends the unit label.
+/// ```
+/// # use weather_dashboard::display::field_value;
+/// # use weather_dashboard::models::{Reading, ReportField};
+/// # use weather_dashboard::{Units, WeatherData};
+/// let weather = WeatherData::new(Reading {
+///     location: "Lima, Peru".to_string(),
+///     temperature: 12.7,
+///     feels_like: 11.25,
+///     humidity: 80,
+///     description: "Mist".to_string(),
+///     wind_speed: 9.0,
+///     wind_gust: None,
+///     source: "WeatherAPI.com".to_string(),
+///     ..Reading::default()
+/// })
+/// .unwrap();
+/// let value = |field, with_unit| field_value(&weather, field, Units::Metric, 1, with_unit);
+/// assert_eq!(value(ReportField::Humidity, false), "80");
+/// assert_eq!(value(ReportField::Humidity, true), "80%");
+/// assert_eq!(value(ReportField::Temp, false), "12.7");
+/// assert_eq!(value(ReportField::Temp, true), "12.7°C");
+/// assert_eq!(value(ReportField::Wind, true), "9.0 km/h");
+/// assert_eq!(value(ReportField::Condition, true), "Mist");
+/// ```
+pub fn field_value(data: &WeatherData, field: ReportField, units: impl Into<UnitPrefs>, digits: usize, with_unit: bool) -> String {
+    let units = units.into();
+    let (value, unit) = match field {
+        ReportField::Temp => (format!("{:.*}", digits, data.temperature), units.temp.temp_label().to_string()),
+        ReportField::FeelsLike => (format!("{:.*}", digits, data.feels_like), units.temp.temp_label().to_string()),
+        ReportField::Humidity => ((*data.humidity).to_string(), "%".to_string()),
+        ReportField::Wind => (format!("{:.*}", digits, data.wind_speed), format!(" {}", units.wind.wind_label())),
+        ReportField::Condition => (data.description.clone(), String::new()),
+    };
+    if with_unit { value + &unit } else { value }
+}
+
+/// Header row matching `csv_row`
+pub const CSV_HEADER: &str = "city,temp,feels_like,humidity,wind,condition";
+
+/// Builds one CSV row: city,temp,feels_like,humidity,wind,condition
+///
+/// Temperatures and wind are rounded to `digits` decimal places:
+/// ```
+/// # use weather_dashboard::display::csv_row;
+/// # use weather_dashboard::models::Reading;
+/// # use weather_dashboard::WeatherData;
+/// let weather = WeatherData::new(Reading {
+///     location: "Lima, Peru".to_string(),
+///     temperature: 12.7,
+///     feels_like: 11.25,
+///     humidity: 80,
+///     description: "Mist".to_string(),
+///     wind_speed: 9.0,
+///     wind_gust: Some(12.0),
+///     source: "WeatherAPI.com".to_string(),
+///     ..Reading::default()
+/// })
+/// .unwrap();
+/// assert_eq!(csv_row("Lima", &weather, 0), "Lima,13,11,80,9,Mist");
+/// assert_eq!(csv_row("Lima", &weather, 2), "Lima,12.70,11.25,80,9.00,Mist");
+/// ```
+pub fn csv_row(city: &str, data: &WeatherData, digits: usize) -> String {
+    format!(
+        "{},{:.*},{:.*},{},{:.*},{}",
+        csv_field(city),
+        digits,
+        data.temperature,
+        digits,
+        data.feels_like,
+        *data.humidity,
+        digits,
+        data.wind_speed,
+        csv_field(&data.description),
+    )
+}
+
+/// Quotes a CSV field if it contains a comma, quote, `\n` or `\r` ("" escapes a quote)
+///
+/// A bare `\r` counts as a line break too - spreadsheets split rows on it.
+/// ```
+/// # use weather_dashboard::display::csv_field;
+/// assert_eq!(csv_field("Lima"), "Lima");
+/// assert_eq!(csv_field("Paris, France"), "\"Paris, France\"");
+/// assert_eq!(csv_field("the \"Big\" Apple"), "\"the \"\"Big\"\" Apple\"");
+/// assert_eq!(csv_field("Rain\nlater"), "\"Rain\nlater\"");
+/// assert_eq!(csv_field("Rain\rlater"), "\"Rain\rlater\"");
+/// assert_eq!(csv_field("Rain\r\nlater"), "\"Rain\r\nlater\"");
+/// ```
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Keeps or strips the styling of a colored string
+fn paint(text: ColoredString, colorize: bool) -> String {
+    if colorize {
+        text.to_string()
+    } else {
+        text.clear().to_string()
+    }
+}
+
+impl WeatherData {
+    /// A Markdown table of the main readings, for pasting into notes
+    ///
+    /// `units` must match what the data was fetched with - it only picks the
+    /// labels. Temperatures and wind are rounded to `digits` decimal places.
+    /// Never colored; a `|` in the conditions is escaped.
+    /// ```
+    /// # use weather_dashboard::models::Reading;
+    /// # use weather_dashboard::{Units, WeatherData};
+    /// let weather = WeatherData::new(Reading {
+    ///     location: "Lima, Peru".to_string(),
+    ///     temperature: 12.7,
+    ///     feels_like: 11.25,
+    ///     humidity: 80,
+    ///     description: "Mist".to_string(),
+    ///     wind_speed: 9.0,
+    ///     wind_gust: None,
+    ///     source: "WeatherAPI.com".to_string(),
+    ///     ..Reading::default()
+    /// })
+    /// .unwrap();
+    /// let markdown = weather.to_markdown(Units::Metric, 1);
+    /// let rows: Vec<&str> = markdown.lines().filter(|line| line.starts_with('|')).collect();
+    /// assert_eq!(rows, [
+    ///     "| Field | Value |",
+    ///     "| --- | --- |",
+    ///     "| Temperature | 12.7°C |",
+    ///     "| Feels like | 11.2°C |",
+    ///     "| Humidity | 80% |",
+    ///     "| Wind | 9.0 km/h |",
+    ///     "| Conditions | Mist |",
+    /// ]);
+    /// assert!(markdown.starts_with("### Lima, Peru\n"));
+    ///
+    /// let precise = weather.to_markdown(Units::Metric, 3);
+    /// assert!(precise.contains("| Feels like | 11.250°C |\n"));
+    /// assert!(precise.contains("| Wind | 9.000 km/h |\n"));
+    /// ```
+    pub fn to_markdown(&self, units: impl Into<UnitPrefs>, digits: usize) -> String {
+        let units = units.into();
+        let (temp_unit, wind_unit) = (units.temp.temp_label(), units.wind.wind_label());
+        let rows = [
+            ("Temperature", format!("{:.*}{}", digits, self.temperature, temp_unit)),
+            ("Feels like", format!("{:.*}{}", digits, self.feels_like, temp_unit)),
+            ("Humidity", self.humidity.to_string()),
+            ("Wind", format!("{:.*} {}", digits, self.wind_speed, wind_unit)),
+            ("Conditions", self.description.replace('|', "\\|")),
+        ];
+
+        let mut markdown = format!("### {}\n\n| Field | Value |\n| --- | --- |\n", self.location);
+        for (field, value) in rows {
+            markdown.push_str(&format!("| {} | {} |\n", field, value));
+        }
+        markdown
+    }
+}
@@ -16,4 +16,110 @@ pub enum WeatherError {
 
     #[error("City not found: {0}")]
     CityNotFound(String),
+
+    #[error("WEATHER_API_KEY is not set. Set WEATHER_API_KEY in your environment or .env file, get a free key at https://www.weatherapi.com/signup.aspx")]
+    MissingApiKey,
+
+    #[error("Could not connect through proxy {0}: {1}")]
+    ProxyError(String, String),
+
+    #[error("No cached weather for {0} - fetch it once while online first")]
+    NoCachedData(String),
+
+    #[error("Unsupported language '{0}' (supported: {})", crate::client::SUPPORTED_LANGUAGES.join(", "))]
+    UnsupportedLanguage(String),
+
+    #[error("Rate limited by the API{}", retry_hint(.0))]
+    RateLimited(Option<u64>),
+
+    #[error("Invalid history date {0}: {1}")]
+    InvalidDate(String, String),
+
+    #[error("Every provider failed: {}", .0.join("; "))]
+    AllProvidersFailed(Vec<String>),
+
+    #[error("Invalid weather reading: {0}")]
+    InvalidReading(String),
+}
+
+impl WeatherError {
+    /// The variant name, e.g. "CityNotFound" - stable for scripts to match on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WeatherError::ApiError(_) => "ApiError",
+            WeatherError::ParseError(_) => "ParseError",
+            WeatherError::NetworkError(_) => "NetworkError",
+            WeatherError::CityNotFound(_) => "CityNotFound",
+            WeatherError::MissingApiKey => "MissingApiKey",
+            WeatherError::ProxyError(..) => "ProxyError",
+            WeatherError::NoCachedData(_) => "NoCachedData",
+            WeatherError::UnsupportedLanguage(_) => "UnsupportedLanguage",
+            WeatherError::RateLimited(_) => "RateLimited",
+            WeatherError::InvalidDate(..) => "InvalidDate",
+            WeatherError::AllProvidersFailed(_) => "AllProvidersFailed",
+            WeatherError::InvalidReading(_) => "InvalidReading",
+        }
+    }
+
+    /// The `--json` error envelope: `{"error": {"kind": ..., "message": ...}}`
+    ///
+    /// Printed to stdout in place of the weather, so a JSON consumer always
+    /// gets something it can parse:
+    /// ```
+    /// # use weather_dashboard::WeatherError;
+    /// let envelope = WeatherError::CityNotFound("Atlantis".to_string()).to_json();
+    /// let parsed: serde_json::Value = serde_json::from_str(&envelope.to_string()).unwrap();
+    /// assert_eq!(parsed["error"]["kind"], "CityNotFound");
+    /// assert_eq!(parsed["error"]["message"], "City not found: Atlantis");
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "error": { "kind": self.kind(), "message": self.to_string() } })
+    }
+}
+
+/// Formats the optional Retry-After seconds for `RateLimited`
+fn retry_hint(retry_after: &Option<u64>) -> String {
+    match retry_after {
+        Some(seconds) => format!(", retry after {}s", seconds),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Reading, WeatherData};
+
+    #[test]
+    fn a_success_payload_is_json_with_the_weather_fields() {
+        let weather = WeatherData::new(Reading {
+            location: "Oslo, Norway".into(),
+            temperature: 5.0,
+            feels_like: 2.0,
+            humidity: 70,
+            description: "Cloudy".into(),
+            wind_speed: 18.0,
+            wind_gust: Some(30.0),
+            source: "test".into(),
+            last_updated_epoch: 0,
+        })
+        .unwrap();
+        // What `--json` prints for a single city
+        let printed = serde_json::to_string_pretty(&weather).unwrap();
+
+        let parsed: serde_json::Value = serde_json::from_str(&printed).unwrap();
+        assert_eq!(parsed["location"], "Oslo, Norway");
+        assert_eq!(parsed["temperature"], 5.0);
+        assert_eq!(parsed["humidity"], 70); // a plain number, not a wrapper object
+        assert_eq!(parsed["wind_gust"], 30.0);
+        assert!(parsed.get("error").is_none());
+    }
+
+    #[test]
+    fn an_error_payload_is_json_with_the_variant_as_kind() {
+        let envelope = WeatherError::RateLimited(Some(30)).to_json();
+        let parsed: serde_json::Value = serde_json::from_str(&envelope.to_string()).unwrap();
+        assert_eq!(parsed["error"]["kind"], "RateLimited");
+        assert_eq!(parsed["error"]["message"], WeatherError::RateLimited(Some(30)).to_string());
+    }
 }
@@ -0,0 +1,26 @@
+//! Weather dashboard library
+//!
+//! Everything except argument parsing lives here, so the fetching and the
+//! report formatting can be tested (or reused) without spawning the CLI.
+
+pub mod alert;
+pub mod batch;
+pub mod cache;
+pub mod client;
+pub mod config;
+pub mod display;
+pub mod error;
+pub mod lookup;
+pub mod models;
+pub mod progress;
+pub mod provider;
+pub mod rate_limit;
+pub mod spinner;
+pub mod weather_log;
+pub mod weather_math;
+
+pub use client::WeatherClient;
+pub use config::load_api_key;
+pub use display::{render_report, ReportView};
+pub use error::WeatherError;
+pub use models::{Percentage, UnitPrefs, Units, WeatherData};
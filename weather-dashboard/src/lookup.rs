@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use crate::batch::{fetch_all, fetch_batch};
+use crate::cache::{self, Cache};
+use crate::client::WeatherClient;
+use crate::error::WeatherError;
+use crate::models::{Astronomy, GeoLocation};
+
+/// Maps each city to a `"lat,lon"` query, consulting the geocode cache before `/search.json`
+///
+/// Only cities the cache doesn't know are looked up, and what they resolve to
+/// is stored for next time. Cities that can't be resolved keep their name as the query.
+pub async fn resolve_cities(
+    client: &WeatherClient,
+    cities: &[String],
+    geocode_cache: &Cache<GeoLocation>,
+) -> HashMap<String, String> {
+    let mut queries = HashMap::new();
+    let mut missing = Vec::new();
+    for city in cities {
+        match geocode_cache.get(&cache::normalize_city(city)) {
+            Some(location) => {
+                queries.insert(city.clone(), location.query());
+            }
+            None => missing.push(city.clone()),
+        }
+    }
+
+    let lookups = fetch_all(&missing, |city| {
+        let client = client.clone();
+        async move { client.geocode(&city).await }
+    })
+    .await;
+
+    for (city, result) in lookups {
+        let query = match result {
+            Ok(location) => {
+                eprintln!("Resolved {} to {}", city, location.resolved_name);
+                let query = location.query();
+                if let Err(e) = geocode_cache.insert(&cache::normalize_city(&city), location) {
+                    eprintln!("Warning: could not update the geocode cache: {}", e);
+                }
+                query
+            }
+            Err(e) => {
+                eprintln!("Warning: could not resolve {}: {}", city, e);
+                city.clone()
+            }
+        };
+        queries.insert(city, query);
+    }
+    queries
+}
+
+/// Sun and moon times for one date, from the astronomy cache where possible
+///
+/// They only change once a day, so entries are keyed by city and `date`.
+pub struct AstronomyLookup<'a> {
+    /// The day to look up, as `YYYY-MM-DD`
+    pub date: &'a str,
+    pub cache: &'a Cache<Astronomy>,
+    /// Ignore cached entries and fetch everything again (the results are still stored)
+    pub refresh: bool,
+    /// End the lookup at the first failed fetch, as `batch::try_fetch_all` does
+    pub stop_on_first_error: bool,
+}
+
+impl AstronomyLookup<'_> {
+    /// Astronomy for each of `cities`, in order, fetching only the ones not cached
+    ///
+    /// `queries` maps a city to what to send the API (e.g. `"lat,lon"` from
+    /// `resolve_cities`); a city without an entry is sent as it is.
+    pub async fn fetch(
+        &self,
+        client: &WeatherClient,
+        cities: &[String],
+        queries: &HashMap<String, String>,
+    ) -> Result<Vec<(String, Result<Astronomy, WeatherError>)>, (String, WeatherError)> {
+        let key = |city: &str| cache::astronomy_key(city, self.date);
+        let mut cached: HashMap<String, Astronomy> = if self.refresh {
+            HashMap::new()
+        } else {
+            cities
+                .iter()
+                .filter_map(|city| self.cache.get(&key(city)).map(|a| (city.clone(), a)))
+                .collect()
+        };
+        let missing: Vec<String> = cities.iter().filter(|c| !cached.contains_key(*c)).cloned().collect();
+
+        let mut fetched: HashMap<String, Result<Astronomy, WeatherError>> =
+            fetch_batch(&missing, self.stop_on_first_error, |city| {
+                let client = client.clone();
+                let query = queries.get(&city).cloned().unwrap_or(city);
+                let date = self.date.to_string();
+                async move { client.fetch_astronomy(&query, &date).await }
+            })
+            .await?
+            .into_iter()
+            .collect();
+
+        for (city, result) in &fetched {
+            if let Ok(astro) = result
+                && let Err(e) = self.cache.insert(&key(city), astro.clone())
+            {
+                eprintln!("Warning: could not update the cache: {}", e);
+            }
+        }
+
+        Ok(cities
+            .iter()
+            .filter_map(|city| {
+                let result = cached.remove(city).map(Ok).or_else(|| fetched.remove(city))?;
+                Some((city.clone(), result))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    /// A cache file of its own for each test, removed again on drop
+    struct TempCache(PathBuf);
+
+    impl TempCache {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("weather-lookup-{}-{}.json", name, std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            TempCache(path)
+        }
+
+        fn open<T: serde::Serialize + serde::de::DeserializeOwned>(&self) -> Cache<T> {
+            Cache::new(&self.0, Duration::from_secs(60))
+        }
+    }
+
+    impl Drop for TempCache {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn a_second_lookup_comes_from_the_geocode_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let search = server
+            .mock("GET", "/search.json")
+            .match_query(mockito::Matcher::UrlEncoded("q".into(), "Paris".into()))
+            .with_status(200)
+            .with_body(r#"[{"name": "Paris", "region": "Ile-de-France", "country": "France", "lat": 48.87, "lon": 2.33}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let temp = TempCache::new("geocode");
+        let geocode_cache = temp.open();
+        let cities = vec!["Paris".to_string()];
+
+        let first = resolve_cities(&client, &cities, &geocode_cache).await;
+        let second = resolve_cities(&client, &cities, &geocode_cache).await;
+
+        assert_eq!(first["Paris"], "48.87,2.33");
+        assert_eq!(second, first);
+        search.assert_async().await; // exactly one request, for the first call
+    }
+
+    #[tokio::test]
+    async fn an_unresolvable_city_keeps_its_name_and_isnt_cached() {
+        let mut server = mockito::Server::new_async().await;
+        let search = server
+            .mock("GET", "/search.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let temp = TempCache::new("geocode-miss");
+        let geocode_cache = temp.open();
+        let cities = vec!["Atlantis".to_string()];
+
+        assert_eq!(resolve_cities(&client, &cities, &geocode_cache).await["Atlantis"], "Atlantis");
+        assert_eq!(resolve_cities(&client, &cities, &geocode_cache).await["Atlantis"], "Atlantis");
+        search.assert_async().await; // asked both times - a miss isn't remembered
+    }
+
+    const ASTRO_BODY: &str = r#"{"astronomy": {"astro": {
+        "sunrise": "04:43 AM", "sunset": "09:21 PM", "moonrise": "11:10 PM",
+        "moonset": "05:02 AM", "moon_phase": "Waxing Gibbous"
+    }}}"#;
+
+    #[tokio::test]
+    async fn a_second_call_on_the_same_date_comes_from_the_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let astronomy = server
+            .mock("GET", "/astronomy.json")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("q".into(), "London".into()),
+                mockito::Matcher::UrlEncoded("dt".into(), "2024-06-21".into()),
+            ]))
+            .with_status(200)
+            .with_body(ASTRO_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let temp = TempCache::new("astronomy");
+        let astronomy_cache = temp.open();
+        let lookup = AstronomyLookup {
+            date: "2024-06-21",
+            cache: &astronomy_cache,
+            refresh: false,
+            stop_on_first_error: false,
+        };
+        let cities = vec!["London".to_string()];
+
+        for _ in 0..2 {
+            let results = lookup.fetch(&client, &cities, &HashMap::new()).await.unwrap();
+            let [(city, Ok(astro))] = &results[..] else { panic!("unexpected results: {:?}", results) };
+            assert_eq!((city.as_str(), astro.sunset.as_str()), ("London", "09:21 PM"));
+        }
+        astronomy.assert_async().await; // exactly one request, for the first call
+    }
+
+    #[tokio::test]
+    async fn refresh_and_a_new_date_both_fetch_again() {
+        let mut server = mockito::Server::new_async().await;
+        let astronomy = server
+            .mock("GET", "/astronomy.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(ASTRO_BODY)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let temp = TempCache::new("astronomy-refresh");
+        let astronomy_cache = temp.open();
+        let cities = vec!["London".to_string()];
+        let lookup = |date, refresh| AstronomyLookup { date, cache: &astronomy_cache, refresh, stop_on_first_error: false };
+
+        lookup("2024-06-21", false).fetch(&client, &cities, &HashMap::new()).await.unwrap();
+        lookup("2024-06-21", true).fetch(&client, &cities, &HashMap::new()).await.unwrap();
+        lookup("2024-06-22", false).fetch(&client, &cities, &HashMap::new()).await.unwrap();
+        astronomy.assert_async().await;
+    }
+}
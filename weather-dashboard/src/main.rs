@@ -1,11 +1,28 @@
-// Module declarations - tells Rust these files are part of our crate
-mod models;
-mod error;
-mod client;
+use std::collections::HashMap;
+use std::fs::File;
+use std::future::Future;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use clap::Parser;
+use anyhow::Context;
+use chrono::NaiveDate;
+use clap::{CommandFactory, Parser, ValueEnum};
+use clap_complete::Shell;
 use colored::Colorize;
-use client::WeatherClient;
+use weather_dashboard::alert::{Alert, Thresholds};
+use weather_dashboard::batch::{self, fetch_all};
+use weather_dashboard::cache::{self, Cache, ASTRONOMY_MAX_AGE, GEOCODE_MAX_AGE};
+use weather_dashboard::client::redact_api_key;
+use weather_dashboard::display::{self, ColorChoice, TempTrend, CSV_HEADER};
+use weather_dashboard::lookup::{resolve_cities, AstronomyLookup};
+use weather_dashboard::models::{self, Astronomy, ForecastDay, GeoLocation, ReportField, SortField};
+use weather_dashboard::progress::DownloadProgress;
+use weather_dashboard::provider::{FallbackProvider, OpenWeatherMapClient, WeatherProvider, OPENWEATHER_KEY_VAR};
+use weather_dashboard::spinner::Spinner;
+use weather_dashboard::{weather_log, weather_math};
+use weather_dashboard::{config, ReportView, UnitPrefs, Units, WeatherClient, WeatherData, WeatherError};
 
 /// CLI Weather Dashboard
 /// Fetches and displays current weather data
@@ -13,12 +30,202 @@ use client::WeatherClient;
 #[command(name = "weather")]
 #[command(about = "A CLI weather dashboard", long_about = None)]
 struct Cli {
-    /// City name to fetch weather for
-    city: String,
+    /// City names to fetch weather for (fetched concurrently)
+    #[arg(required_unless_present_any = ["search", "stdin", "clear_cache", "compare", "generate_completions"])]
+    cities: Vec<String>,
 
-    /// Units: metric or imperial
-    #[arg(short, long, default_value = "metric")]
-    units: String,
+    /// Also read city names from stdin, one per line (blank lines are skipped).
+    /// With --format json each city is written as its own JSON line.
+    #[arg(long)]
+    stdin: bool,
+
+    /// List locations matching a name instead of fetching weather
+    #[arg(long, value_name = "QUERY")]
+    search: Option<String>,
+
+    /// Compare two cities side by side instead of printing full reports
+    #[arg(long, num_args = 2, value_names = ["CITY_A", "CITY_B"], conflicts_with_all = ["forecast", "stdin"])]
+    compare: Option<Vec<String>>,
+
+    /// Units: metric, imperial or kelvin (also c, f, k) - the default for every field
+    #[arg(short, long, default_value_t = Units::Metric)]
+    units: Units,
+
+    /// Units for temperatures only - overrides --units
+    #[arg(long)]
+    temp_units: Option<Units>,
+
+    /// Units for wind speed only - overrides --units (kelvin means km/h)
+    #[arg(long)]
+    wind_units: Option<Units>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Shorthand for --format json
+    #[arg(long)]
+    json: bool,
+
+    /// Language for the condition text, e.g. es, fr, de (labels stay English)
+    #[arg(long, value_name = "CODE")]
+    lang: Option<String>,
+
+    /// Print only this value: temp, feels_like, humidity, wind or condition (no label or color)
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["forecast", "history", "astronomy", "raw", "compare", "format", "json"])]
+    field: Option<ReportField>,
+
+    /// With --field, follow the value with its unit, e.g. 21.0°C
+    #[arg(long, requires = "field")]
+    with_unit: bool,
+
+    /// Decimal places for temperatures and wind in text and CSV output (JSON keeps full precision)
+    #[arg(long, default_value_t = display::DEFAULT_DIGITS)]
+    digits: usize,
+
+    /// Print the request URL for each city (API key masked) and exit without calling the API
+    #[arg(long, conflicts_with_all = ["forecast", "history", "astronomy", "raw", "compare", "search", "offline"])]
+    dry_run: bool,
+
+    /// Print the API's response body as-is instead of a report (for debugging)
+    #[arg(long, conflicts_with_all = ["forecast", "offline", "compare", "format", "json"])]
+    raw: bool,
+
+    /// Also append each fetched reading to this file as a JSON line, with a timestamp
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["forecast", "history", "astronomy", "raw", "compare", "offline"])]
+    log: Option<PathBuf>,
+
+    /// Write the report to a file instead of stdout (colors are stripped)
+    #[arg(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Show a daily forecast instead of the current weather
+    #[arg(long)]
+    forecast: bool,
+
+    /// Show the weather on a past date (YYYY-MM-DD) instead - needs a paid API plan
+    #[arg(long, value_name = "DATE", conflicts_with_all = ["forecast", "astronomy", "raw", "offline", "compare"])]
+    history: Option<NaiveDate>,
+
+    /// Show how the temperature changed since the last cached reading, e.g. "12°C ↑ (+2° since last)".
+    /// Cities served from the fresh cache have no new reading, so no trend.
+    #[arg(long, conflicts_with_all = ["forecast", "history", "astronomy", "raw", "compare", "offline"])]
+    temp_trend: bool,
+
+    /// Show feels-like computed from wind chill / heat index instead of the API's value
+    #[arg(long, conflicts_with_all = ["forecast", "history", "astronomy", "raw", "compare"])]
+    computed_feels_like: bool,
+
+    /// Order the cities by temp, humidity or wind (lowest first); failed cities go last
+    #[arg(long, value_name = "FIELD", conflicts_with_all = ["forecast", "history", "astronomy", "raw", "compare"])]
+    sort: Option<SortField>,
+
+    /// With --sort, put the highest values first
+    #[arg(long, requires = "sort")]
+    desc: bool,
+
+    /// Warn (and exit with code 1) if a city's temperature is above this
+    #[arg(long, value_name = "TEMP", allow_negative_numbers = true, conflicts_with_all = ALERT_CONFLICTS)]
+    alert_temp_above: Option<f64>,
+
+    /// Warn (and exit with code 1) if a city's temperature is below this
+    #[arg(long, value_name = "TEMP", allow_negative_numbers = true, conflicts_with_all = ALERT_CONFLICTS)]
+    alert_temp_below: Option<f64>,
+
+    /// Warn (and exit with code 1) if a city's wind speed is above this
+    #[arg(long, value_name = "SPEED", conflicts_with_all = ALERT_CONFLICTS)]
+    alert_wind_above: Option<f64>,
+
+    /// Show today's sunrise, sunset and moon times instead of the weather (cached per day)
+    #[arg(long, conflicts_with_all = ["forecast", "raw", "offline", "compare"])]
+    astronomy: bool,
+
+    /// Ignore cached astronomy data and fetch it again
+    #[arg(long, requires = "astronomy")]
+    refresh_astronomy: bool,
+
+    /// Show download progress on stderr while the forecast is fetched
+    #[arg(long, requires = "forecast")]
+    percent: bool,
+
+    /// Number of forecast days (1-14)
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=14))]
+    days: u8,
+
+    /// Maximum API requests per second; extra requests wait instead of failing
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    max_rps: Option<u32>,
+
+    /// Resolve city names to coordinates first (cached), so a name always maps to the same place
+    #[arg(long)]
+    geocode: bool,
+
+    /// Seconds fetched weather is reused from the cache
+    #[arg(long, value_name = "SECONDS", default_value_t = cache::WEATHER_MAX_AGE.as_secs())]
+    cache_ttl: u64,
+
+    /// Never touch the network: show the last cached weather, however old
+    #[arg(long, conflicts_with_all = ["forecast", "search", "compare"])]
+    offline: bool,
+
+    /// Delete the cached weather and geocode data
+    #[arg(long)]
+    clear_cache: bool,
+
+    /// Proxy URL for all requests; overrides HTTP_PROXY/HTTPS_PROXY
+    #[arg(long, value_name = "URL")]
+    proxy: Option<String>,
+
+    /// If WeatherAPI.com fails, try OpenWeatherMap (needs OPENWEATHER_API_KEY)
+    #[arg(long, conflicts_with_all = ["forecast", "history", "astronomy", "raw", "compare", "offline", "geocode"])]
+    provider_fallback: bool,
+
+    /// When to color the output: auto (only on a terminal, off if NO_COLOR is set), always or never
+    #[arg(long, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Disable colored output - same as --color never
+    #[arg(long, conflicts_with = "color")]
+    no_color: bool,
+
+    /// Use plain text instead of emoji icons
+    #[arg(long)]
+    no_emoji: bool,
+
+    /// Print extra details on stderr, e.g. which API key is used (masked)
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Don't animate a spinner on stderr while waiting for the API
+    #[arg(long)]
+    no_spinner: bool,
+
+    /// Stop at the first city that fails (exit code 1) instead of reporting the rest.
+    /// Cities are fetched concurrently, so other requests may already have been sent.
+    #[arg(long, conflicts_with = "compare")]
+    exit_on_first_error: bool,
+
+    /// Print a tab-completion script for bash, zsh, fish, powershell or elvish and exit
+    #[arg(long, value_name = "SHELL", hide = true)]
+    generate_completions: Option<Shell>,
+}
+
+/// Modes the --alert-* limits don't apply to - they only check current weather
+const ALERT_CONFLICTS: [&str; 6] = ["forecast", "history", "astronomy", "raw", "compare", "search"];
+
+/// How the results are written to stdout (or the --output file)
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable colored report
+    Text,
+    /// The text report with its values lined up in one column
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// One header row plus one row per city
+    Csv,
+    /// A Markdown table per city, for pasting into notes (never colored)
+    Markdown,
 }
 
 // The #[tokio::main] macro transforms this into:
@@ -34,32 +241,661 @@ async fn main() -> anyhow::Result<()> {
 
     // Parse command line arguments
     let cli = Cli::parse();
+    if let Some(shell) = cli.generate_completions {
+        io::stdout().write_all(completion_script(shell).as_bytes())?;
+        return Ok(());
+    }
+    let format = if cli.json { OutputFormat::Json } else { cli.format };
+
+    let (result, mut out) = match open_output(&cli) {
+        Ok(mut out) => (run(cli, format, &mut out).await, out),
+        // No --output file to write to, so a JSON error goes to stdout
+        Err(e) => (Err(e), Box::new(io::stdout().lock()) as Box<dyn Write>),
+    };
+    // JSON consumers get a parseable error where the report would have gone; the exit code is still non-zero
+    if let Err(e) = &result
+        && format == OutputFormat::Json
+        && let Err(write_error) = write_error_envelope(&mut out, e)
+    {
+        // A closed pipe is no reason to panic - the exit code still reports the failure
+        eprintln!("Failed to write the JSON error: {}", write_error);
+    }
+    if let Err(e) = &result
+        && is_usage_error(e)
+    {
+        eprintln!("{} {}", "Error:".red().bold(), e);
+        std::process::exit(2);
+    }
+    result
+}
+
+/// Errors that mean the tool was set up wrong rather than that a fetch failed
+///
+/// Like a bad flag (which clap reports with exit code 2), these exit with code 2;
+/// everything else exits with 1.
+fn is_usage_error(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<WeatherError>(), Some(WeatherError::MissingApiKey))
+}
+
+/// The completion script for `shell`, covering every flag of `Cli`
+///
+/// Install with e.g. `weather --generate-completions bash > ~/.local/share/bash-completion/completions/weather`.
+fn completion_script(shell: Shell) -> String {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "weather", &mut script);
+    String::from_utf8(script).expect("clap_complete writes UTF-8")
+}
+
+/// Writes `error` as the `{"error": {"kind", "message"}}` object `--json` consumers parse
+fn write_error_envelope(out: &mut dyn Write, error: &anyhow::Error) -> io::Result<()> {
+    let envelope = match error.downcast_ref::<WeatherError>() {
+        Some(e) => e.to_json(),
+        None => serde_json::json!({ "error": { "kind": "Other", "message": error.to_string() } }),
+    };
+    writeln!(out, "{}", serde_json::to_string_pretty(&envelope)?)?;
+    out.flush()
+}
+
+/// Where the report goes: a file if --output was given, otherwise stdout
+fn open_output(cli: &Cli) -> anyhow::Result<Box<dyn Write>> {
+    Ok(match &cli.output {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("Failed to create output file {}", path.display()))?;
+            Box::new(file)
+        }
+        None => Box::new(io::stdout().lock()),
+    })
+}
+
+/// Everything after argument parsing - `main` wraps it to report errors
+///
+/// The report is written to `out`, which `main` opened with `open_output`.
+async fn run(mut cli: Cli, format: OutputFormat, out: &mut dyn Write) -> anyhow::Result<()> {
+    let choice = if cli.no_color { ColorChoice::Never } else { cli.color };
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let color = choice.enabled(io::stdout().is_terminal(), no_color_env);
+    colored::control::set_override(color);
+
+    let cache_dir = cache::default_cache_dir();
+    let weather_cache: Cache<WeatherData> =
+        Cache::new(cache_dir.join("weather.json"), Duration::from_secs(cli.cache_ttl));
+    let geocode_cache: Cache<GeoLocation> = Cache::new(cache_dir.join("geocode.json"), GEOCODE_MAX_AGE);
+    let astronomy_cache: Cache<Astronomy> = Cache::new(cache_dir.join("astronomy.json"), ASTRONOMY_MAX_AGE);
+
+    if cli.clear_cache {
+        weather_cache.clear().context("Failed to clear the weather cache")?;
+        geocode_cache.clear().context("Failed to clear the geocode cache")?;
+        astronomy_cache.clear().context("Failed to clear the astronomy cache")?;
+        eprintln!("Cache cleared ({})", cache_dir.display());
+        if cli.cities.is_empty() && !cli.stdin {
+            return Ok(());
+        }
+    }
+
+    if let Some(query) = &cli.search {
+        return print_search_results(&build_client(&cli)?, query).await;
+    }
+
+    if let Some(pair) = &cli.compare {
+        return print_comparison(&build_client(&cli)?, pair, unit_prefs(&cli), cli.digits, color).await;
+    }
+
+    if cli.stdin {
+        cli.cities.extend(read_cities(io::stdin().lock())?);
+        if cli.cities.is_empty() {
+            anyhow::bail!("No city names received on stdin");
+        }
+    }
 
-    // Get API key from environment variable
-    let api_key = std::env::var("WEATHER_API_KEY")
-        .expect("WEATHER_API_KEY must be set in .env file");
+    if cli.dry_run {
+        let client = build_client(&cli)?;
+        for city in &cli.cities {
+            eprintln!("{}", redact_api_key(&client.build_url(city)));
+        }
+        return Ok(());
+    }
 
-    println!("{}", format!("🌤️  Fetching weather for {}...", cli.city).cyan());
+    if cli.offline {
+        eprintln!("{}", format!("📦 Reading cached weather for {}...", cli.cities.join(", ")).cyan());
+    } else {
+        eprintln!("{}", format!("🌤️  Fetching weather for {}...", cli.cities.join(", ")).cyan());
+    }
+
+    // Files never get ANSI color codes - they'd just be noise in the saved report
+    let colorize = cli.output.is_none() && color;
 
-    // Create client and fetch weather
-    let client = WeatherClient::new(api_key);
-    let weather = client.fetch_weather(&cli.city, &cli.units).await?;
+    let units = unit_prefs(&cli);
+    // A --lang reading gets its own key - its condition text is already translated
+    let lang = cli.lang.as_ref().map(|code| format!("|{}", code.to_ascii_lowercase())).unwrap_or_default();
+    let cache_key = |city: &str| format!("{}|{:?}|{:?}{}", cache::normalize_city(city), units.temp, units.wind, lang);
 
-    // Display results with colors!
-    let (temp_unit, wind_unit) = if cli.units == "imperial" {
-        ("°F", "mph")
+    if cli.offline {
+        // The newest cached reading per city, whatever its age - no client, no API key
+        let mut ages = HashMap::new();
+        let results = cli
+            .cities
+            .iter()
+            .map(|city| {
+                let result = match weather_cache.get_with_age(&cache_key(city)) {
+                    Some((weather, age)) => {
+                        ages.insert(city.clone(), age);
+                        Ok(weather)
+                    }
+                    None => Err(WeatherError::NoCachedData(city.clone())),
+                };
+                (city.clone(), result)
+            })
+            .collect();
+        report_current(out, &cli, format, colorize, results, &ages, &HashMap::new())?;
+        return Ok(());
+    }
+
+    let client = build_client(&cli)?;
+    let queries = if cli.geocode {
+        resolve_cities(&client, &cli.cities, &geocode_cache).await
     } else {
-        ("°C", "km/h")
+        cli.cities.iter().map(|city| (city.clone(), city.clone())).collect()
     };
 
-    println!("\n{}", "Weather Report".bold().underline());
-    println!("{}: {}", "City".bold(), cli.city);
-    println!("{}: {}{}", "Temperature".bold(), weather.temperature.to_string().yellow(), temp_unit);
-    println!("{}: {}{}", "Feels like".bold(), weather.feels_like.to_string().yellow(), temp_unit);
-    println!("{}: {}%", "Humidity".bold(), weather.humidity.to_string().blue());
-    println!("{}: {}", "Conditions".bold(), weather.description);
-    println!("{}: {} {}", "Wind speed".bold(), weather.wind_speed.to_string().green(), wind_unit);
-    println!("{}: {}", "Source".bold(), weather.source.dimmed());
+    if cli.raw {
+        let results = fetch_batch(&cli, &cli.cities, |city| {
+            let client = client.clone();
+            let query = queries[&city].clone();
+            async move { client.fetch_raw(&query).await }
+        })
+        .await?;
+        for (_, body) in keep_successes(results)? {
+            writeln!(out, "{}", body)?;
+        }
+    } else if cli.astronomy {
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let lookup = AstronomyLookup {
+            date: &date,
+            cache: &astronomy_cache,
+            refresh: cli.refresh_astronomy,
+            stop_on_first_error: cli.exit_on_first_error,
+        };
+        let results = lookup.fetch(&client, &cli.cities, &queries).await.map_err(stopped)?;
+        write_astronomy(out, format, colorize, &date, keep_successes(results)?)?;
+    } else if let Some(date) = cli.history {
+        let units = units.temp;
+        let results = fetch_batch(&cli, &cli.cities, |city| {
+            let client = client.clone();
+            let query = queries[&city].clone();
+            async move { client.fetch_history(&query, date, units).await }
+        })
+        .await?;
+        write_history(out, &cli, format, colorize, keep_successes(results)?)?;
+    } else if cli.forecast {
+        let (days, show_progress) = (cli.days, cli.percent);
+        // One line for all the downloads, redrawn under the lock by whichever city made progress
+        let progress = Arc::new(Mutex::new(DownloadProgress::new(cli.cities.iter().cloned())));
+        let results = fetch_batch(&cli, &cli.cities, |city| {
+            let client = client.clone();
+            let query = queries[&city].clone();
+            let progress = Arc::clone(&progress);
+            async move {
+                let on_progress = |received: u64, total: Option<u64>| {
+                    if !show_progress {
+                        return;
+                    }
+                    let mut progress = progress.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    // Progress is only decoration: a closed stderr mustn't fail the fetch
+                    let _ = progress.update(&mut io::stderr().lock(), &city, received, total);
+                };
+                client.fetch_forecast_with_progress(&query, days, units.temp, on_progress).await
+            }
+        })
+        .await?;
+        if show_progress {
+            eprintln!();
+        }
+        write_forecasts(out, &cli, format, colorize, keep_successes(results)?)?;
+    } else {
+        // Fresh cached readings skip the network entirely
+        let mut cached: HashMap<String, WeatherData> = cli
+            .cities
+            .iter()
+            .filter_map(|city| weather_cache.get(&cache_key(city)).map(|w| (city.clone(), w)))
+            .collect();
+        let missing: Vec<String> = cli.cities.iter().filter(|c| !cached.contains_key(*c)).cloned().collect();
+
+        let provider: Arc<dyn WeatherProvider> = if cli.provider_fallback {
+            let fallback = OpenWeatherMapClient::from_env()
+                .with_context(|| format!("--provider-fallback needs {} to be set", OPENWEATHER_KEY_VAR))?;
+            Arc::new(FallbackProvider::new(vec![Box::new(client.clone()), Box::new(fallback)]))
+        } else {
+            Arc::new(client.clone())
+        };
+
+        // Fetch every remaining city at the same time
+        let show_spinner = !missing.is_empty() && !cli.no_spinner;
+        let spinner = show_spinner.then(|| Spinner::start("Waiting for WeatherAPI.com...")).flatten();
+        let fetched = fetch_batch(&cli, &missing, |city| {
+            let provider = provider.clone();
+            let query = queries[&city].clone();
+            async move { provider.fetch_weather(&query, units).await }
+        })
+        .await;
+        if let Some(spinner) = spinner {
+            spinner.stop().await;
+        }
+        let mut fetched: HashMap<String, Result<WeatherData, WeatherError>> = fetched?.into_iter().collect();
+
+        let mut trends = HashMap::new();
+        for (city, result) in &fetched {
+            let Ok(weather) = result else { continue };
+            // Read the old entry (however stale) before the new reading replaces it
+            if cli.temp_trend
+                && let Some((previous, _)) = weather_cache.get_with_age(&cache_key(city))
+            {
+                trends.insert(city.clone(), TempTrend::between(previous.temperature, weather.temperature));
+            }
+            if let Err(e) = weather_cache.insert(&cache_key(city), weather.clone()) {
+                eprintln!("Warning: could not update the cache: {}", e);
+            }
+        }
+
+        // Back to the order the cities were given in
+        let results = cli
+            .cities
+            .iter()
+            .filter_map(|city| {
+                let result = cached.remove(city).map(Ok).or_else(|| fetched.remove(city))?;
+                Some((city.clone(), result))
+            })
+            .collect();
+        report_current(out, &cli, format, colorize, results, &HashMap::new(), &trends)?;
+    }
+
+    if let Some(path) = &cli.output {
+        eprintln!("Report written to {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Builds the API client from the environment and the --max-rps/--proxy flags
+///
+/// A missing API key comes back as `WeatherError::MissingApiKey`, which `main`
+/// treats as a usage error (exit code 2 - see `is_usage_error`).
+fn build_client(cli: &Cli) -> anyhow::Result<WeatherClient> {
+    let api_key = config::load_api_key()?;
+
+    if cli.verbose {
+        eprintln!("Using {} {}", config::API_KEY_VAR, config::mask_secret(&api_key));
+    }
+
+    let mut client = WeatherClient::new(api_key);
+    if let Some(max_rps) = cli.max_rps {
+        client = client.with_max_rps(max_rps);
+    }
+    if let Some(proxy) = &cli.proxy {
+        client = client.with_proxy(proxy)?;
+    }
+    if let Some(lang) = &cli.lang {
+        client = client.with_lang(lang)?;
+    }
+    Ok(client)
+}
+
+/// Reads one city name per line until EOF, skipping blank lines
+fn read_cities<R: BufRead>(input: R) -> io::Result<Vec<String>> {
+    let mut cities = Vec::new();
+    for line in input.lines() {
+        let line = line?;
+        let city = line.trim();
+        if !city.is_empty() {
+            cities.push(city.to_string());
+        }
+    }
+    Ok(cities)
+}
+
+/// Prints the numbered candidates for an ambiguous place name
+async fn print_search_results(client: &WeatherClient, query: &str) -> anyhow::Result<()> {
+    let matches = client.search_cities(query).await?;
+
+    if matches.is_empty() {
+        eprintln!("No matches found for '{}'", query);
+        return Ok(());
+    }
+
+    println!("{}", format!("Locations matching '{}':", query).bold());
+    for (i, city) in matches.iter().enumerate() {
+        println!(
+            "{:>3}. {}, {}, {} ({})",
+            i + 1,
+            city.name,
+            city.region,
+            city.country,
+            format!("{},{}", city.lat, city.lon).dimmed()
+        );
+    }
+    eprintln!("\nTip: pass the coordinates as the city, e.g. weather \"{},{}\"", matches[0].lat, matches[0].lon);
+
+    Ok(())
+}
+
+/// Fetches both cities concurrently and prints them side by side
+///
+/// A city that fails is reported on stderr and shown as N/A; only both failing is an error.
+async fn print_comparison(
+    client: &WeatherClient,
+    pair: &[String],
+    units: UnitPrefs,
+    digits: usize,
+    colorize: bool,
+) -> anyhow::Result<()> {
+    let results = fetch_all(pair, |city| {
+        let client = client.clone();
+        async move { client.fetch_weather(&city, units).await }
+    })
+    .await;
+    let successes: HashMap<String, WeatherData> = keep_successes(results)?.into_iter().collect();
 
+    let (a, b) = (&pair[0], &pair[1]);
+    print!("{}", display::render_comparison((a, successes.get(a)), (b, successes.get(b)), units, digits, colorize));
     Ok(())
 }
+
+/// Resolves the per-field units: --temp-units/--wind-units win, otherwise --units applies
+fn unit_prefs(cli: &Cli) -> UnitPrefs {
+    UnitPrefs {
+        temp: cli.temp_units.unwrap_or(cli.units),
+        wind: cli.wind_units.unwrap_or(cli.units),
+    }
+}
+
+/// Sorts, reports and checks alerts for the current-weather results, live or cached
+fn report_current(
+    out: &mut dyn Write,
+    cli: &Cli,
+    format: OutputFormat,
+    colorize: bool,
+    mut results: Vec<(String, Result<WeatherData, WeatherError>)>,
+    cached_ages: &HashMap<String, Duration>,
+    trends: &HashMap<String, TempTrend>,
+) -> anyhow::Result<()> {
+    if let Some(field) = cli.sort {
+        models::sort_results(&mut results, field, cli.desc);
+    }
+    let mut successes = keep_successes(results)?;
+    if cli.computed_feels_like {
+        for (_, weather) in &mut successes {
+            weather.feels_like = weather_math::computed_feels_like(weather, unit_prefs(cli));
+        }
+    }
+    if let Some(path) = &cli.log {
+        weather_log::append(path, unit_prefs(cli), successes.iter().map(|(_, weather)| weather))
+            .with_context(|| format!("Failed to append to log file {}", path.display()))?;
+    }
+    let alerts = check_alerts(cli, &successes);
+    write_current(out, cli, format, colorize, successes, cached_ages, trends)?;
+    exit_on_alerts(out, &alerts)
+}
+
+/// Every alert limit the fetched cities cross
+fn check_alerts(cli: &Cli, successes: &[(String, WeatherData)]) -> Vec<Alert> {
+    let thresholds = Thresholds {
+        temp_above: cli.alert_temp_above,
+        temp_below: cli.alert_temp_below,
+        wind_above: cli.alert_wind_above,
+    };
+    successes
+        .iter()
+        .flat_map(|(city, weather)| thresholds.check(city, weather))
+        .collect()
+}
+
+/// Prints the alerts in red on stderr and exits with code 1 if there are any
+///
+/// The report is flushed first - `process::exit` skips destructors.
+fn exit_on_alerts(out: &mut dyn Write, alerts: &[Alert]) -> anyhow::Result<()> {
+    if alerts.is_empty() {
+        return Ok(());
+    }
+    out.flush()?;
+    for alert in alerts {
+        eprintln!("{} {}", "⚠ ALERT:".red().bold(), alert.to_string().red());
+    }
+    std::process::exit(1);
+}
+
+/// Writes the current-weather results in the chosen format
+///
+/// `cached_ages` holds how old each offline reading is, and `trends` how far
+/// each fetched temperature moved since the last cached one; text reports show both.
+fn write_current(
+    out: &mut dyn Write,
+    cli: &Cli,
+    format: OutputFormat,
+    colorize: bool,
+    successes: Vec<(String, WeatherData)>,
+    cached_ages: &HashMap<String, Duration>,
+    trends: &HashMap<String, TempTrend>,
+) -> anyhow::Result<()> {
+    // One bare value per city, for scripts: TEMP=$(weather --field temp London)
+    if let Some(field) = cli.field {
+        for (_, weather) in &successes {
+            writeln!(out, "{}", display::field_value(weather, field, unit_prefs(cli), cli.digits, cli.with_unit))?;
+        }
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Text | OutputFormat::Table => {
+            for (city, weather) in &successes {
+                let report = ReportView::new(weather, unit_prefs(cli))
+                    .colorized(colorize)
+                    .digits(cli.digits)
+                    .aligned(format == OutputFormat::Table)
+                    .trend(trends.get(city).copied());
+                write!(out, "{}", report)?;
+                if let Some(age) = cached_ages.get(city) {
+                    let note = format!("(cached {})", display::format_age(*age));
+                    writeln!(out, "{}", if colorize { note.dimmed().to_string() } else { note })?;
+                }
+            }
+        }
+        OutputFormat::Json if cli.stdin => {
+            // Streaming input gets streaming output: one JSON object per line
+            for (_, weather) in &successes {
+                writeln!(out, "{}", serde_json::to_string(weather)?)?;
+            }
+        }
+        OutputFormat::Json => {
+            // A single city keeps the plain object shape; several become an array
+            let json = match successes.as_slice() {
+                [(_, weather)] => serde_json::to_string_pretty(weather)?,
+                _ => {
+                    let all: Vec<&WeatherData> = successes.iter().map(|(_, w)| w).collect();
+                    serde_json::to_string_pretty(&all)?
+                }
+            };
+            writeln!(out, "{}", json)?;
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "{}", CSV_HEADER)?;
+            for (city, weather) in &successes {
+                writeln!(out, "{}", display::csv_row(city, weather, cli.digits))?;
+            }
+        }
+        OutputFormat::Markdown => {
+            // A blank line between tables keeps them from merging into one
+            let tables: Vec<String> = successes.iter().map(|(_, w)| w.to_markdown(unit_prefs(cli), cli.digits)).collect();
+            write!(out, "{}", tables.join("\n"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the forecast results - a table per city, or JSON keyed by city
+fn write_forecasts(
+    out: &mut dyn Write,
+    cli: &Cli,
+    format: OutputFormat,
+    colorize: bool,
+    successes: Vec<(String, Vec<ForecastDay>)>,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Text | OutputFormat::Table => {
+            for (city, days) in &successes {
+                write!(out, "{}", display::render_forecast(city, days, unit_prefs(cli).temp, colorize, !cli.no_emoji))?;
+            }
+        }
+        OutputFormat::Json => {
+            let all: serde_json::Map<String, serde_json::Value> = successes
+                .into_iter()
+                .map(|(city, days)| Ok((city, serde_json::to_value(days)?)))
+                .collect::<Result<_, serde_json::Error>>()?;
+            writeln!(out, "{}", serde_json::to_string_pretty(&all)?)?;
+        }
+        OutputFormat::Csv => anyhow::bail!("CSV output is not supported with --forecast"),
+        OutputFormat::Markdown => anyhow::bail!("Markdown output is not supported with --forecast"),
+    }
+    Ok(())
+}
+
+/// Writes the history results - one day per city, or JSON keyed by city
+fn write_history(
+    out: &mut dyn Write,
+    cli: &Cli,
+    format: OutputFormat,
+    colorize: bool,
+    successes: Vec<(String, ForecastDay)>,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Text | OutputFormat::Table => {
+            for (city, day) in &successes {
+                write!(out, "{}", display::render_history(city, day, unit_prefs(cli).temp, colorize, !cli.no_emoji))?;
+            }
+        }
+        OutputFormat::Json => {
+            let all: HashMap<String, ForecastDay> = successes.into_iter().collect();
+            writeln!(out, "{}", serde_json::to_string_pretty(&all)?)?;
+        }
+        OutputFormat::Csv => anyhow::bail!("CSV output is not supported with --history"),
+        OutputFormat::Markdown => anyhow::bail!("Markdown output is not supported with --history"),
+    }
+    Ok(())
+}
+
+/// Writes the astronomy results - a block per city, or JSON keyed by city
+fn write_astronomy(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    colorize: bool,
+    date: &str,
+    successes: Vec<(String, Astronomy)>,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Text | OutputFormat::Table => {
+            for (city, astro) in &successes {
+                write!(out, "{}", display::render_astronomy(city, date, astro, colorize))?;
+            }
+        }
+        OutputFormat::Json => {
+            let all: HashMap<String, Astronomy> = successes.into_iter().collect();
+            writeln!(out, "{}", serde_json::to_string_pretty(&all)?)?;
+        }
+        OutputFormat::Csv => anyhow::bail!("CSV output is not supported with --astronomy"),
+        OutputFormat::Markdown => anyhow::bail!("Markdown output is not supported with --astronomy"),
+    }
+    Ok(())
+}
+
+/// Reports failed cities on stderr and keeps the rest
+///
+/// Only gives up entirely (returning the last error) when every city failed.
+fn keep_successes<T>(results: Vec<(String, Result<T, WeatherError>)>) -> anyhow::Result<Vec<(String, T)>> {
+    // Failed cities are reported on stderr so the data stream stays clean
+    let mut successes = Vec::new();
+    let mut last_error = None;
+    for (city, result) in results {
+        match result {
+            Ok(value) => successes.push((city, value)),
+            Err(e) => {
+                eprintln!("{} {}: {}", "Error:".red().bold(), city, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    if successes.is_empty() && let Some(e) = last_error {
+        return Err(e.into());
+    }
+    Ok(successes)
+}
+
+/// `batch::fetch_batch`: with --exit-on-first-error the first failed city ends the run
+async fn fetch_batch<T, F, Fut>(
+    cli: &Cli,
+    cities: &[String],
+    fetch: F,
+) -> anyhow::Result<Vec<(String, Result<T, WeatherError>)>>
+where
+    T: Send + 'static,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, WeatherError>> + Send + 'static,
+{
+    batch::fetch_batch(cities, cli.exit_on_first_error, fetch).await.map_err(stopped)
+}
+
+/// The error ending an --exit-on-first-error run at `city`
+fn stopped((city, e): (String, WeatherError)) -> anyhow::Error {
+    anyhow::Error::from(e).context(format!("{} failed, stopping", city))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_missing_api_key_is_a_usage_error() {
+        assert!(is_usage_error(&WeatherError::MissingApiKey.into()));
+        // Still recognised with context wrapped around it
+        let wrapped = anyhow::Error::from(WeatherError::MissingApiKey).context("Failed to build the client");
+        assert!(is_usage_error(&wrapped));
+
+        assert!(!is_usage_error(&WeatherError::CityNotFound("Atlantis".into()).into()));
+        assert!(!is_usage_error(&anyhow::anyhow!("something else")));
+    }
+
+    #[test]
+    fn the_json_error_envelope_goes_to_the_given_writer() {
+        let mut out = Vec::new();
+        write_error_envelope(&mut out, &WeatherError::CityNotFound("Atlantis".into()).into()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["error"]["kind"], "CityNotFound");
+
+        out.clear();
+        write_error_envelope(&mut out, &anyhow::anyhow!("disk full")).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!((&parsed["error"]["kind"], &parsed["error"]["message"]), (&"Other".into(), &"disk full".into()));
+    }
+
+    #[test]
+    fn a_closed_pipe_is_an_error_not_a_panic() {
+        struct ClosedPipe;
+        impl Write for ClosedPipe {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let error = write_error_envelope(&mut ClosedPipe, &anyhow::anyhow!("no network")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn bash_completions_cover_the_cli_flags() {
+        let script = completion_script(Shell::Bash);
+        assert!(!script.is_empty());
+        assert!(script.contains("--units"));
+    }
+}
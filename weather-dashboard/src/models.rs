@@ -1,19 +1,561 @@
-use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use chrono::{DateTime, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use crate::error::WeatherError;
+
+/// Add to °C to get kelvin
+pub const KELVIN_OFFSET: f64 = 273.15;
+
+/// Kilometres in a mile
+pub const KPH_PER_MPH: f64 = 1.609344;
+
+/// Measurement system used for the fetched values and their labels
+///
+/// Serializes as its lowercase name, the same as `Display`: `"metric"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// °C and km/h
+    #[default]
+    Metric,
+    /// °F and mph
+    Imperial,
+    /// K for temperatures, km/h for wind
+    Kelvin,
+}
+
+/// A units name that `Units::from_str` doesn't recognise
+#[derive(Debug, Error)]
+#[error("unknown units '{0}' (expected metric, imperial or kelvin - or c, f, k)")]
+pub struct UnknownUnits(String);
+
+/// The one place unit names are parsed - the CLI and anything else reading
+/// a units setting go through here
+///
+/// Case-insensitive, with short and temperature-scale aliases:
+/// ```
+/// # use weather_dashboard::Units;
+/// assert_eq!("Metric".parse::<Units>().unwrap(), Units::Metric);
+/// assert_eq!("f".parse::<Units>().unwrap(), Units::Imperial);
+/// assert_eq!("kelvin".parse::<Units>().unwrap(), Units::Kelvin);
+/// assert!("rankine".parse::<Units>().is_err());
+/// ```
+impl FromStr for Units {
+    type Err = UnknownUnits;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "metric" | "c" | "celsius" => Ok(Units::Metric),
+            "imperial" | "f" | "fahrenheit" => Ok(Units::Imperial),
+            "kelvin" | "k" => Ok(Units::Kelvin),
+            _ => Err(UnknownUnits(s.to_string())),
+        }
+    }
+}
+
+/// The canonical lowercase name, which `from_str` reads back
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+            Units::Kelvin => "kelvin",
+        })
+    }
+}
+
+impl Units {
+    /// Label printed after temperatures
+    pub fn temp_label(self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+            Units::Kelvin => "K",
+        }
+    }
+
+    /// Label printed after wind speeds
+    pub fn wind_label(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Kelvin => "km/h",
+            Units::Imperial => "mph",
+        }
+    }
+
+    /// Converts a temperature in these units to °C
+    pub fn to_celsius(self, temp: f64) -> f64 {
+        match self {
+            Units::Metric => temp,
+            Units::Imperial => (temp - 32.0) * 5.0 / 9.0,
+            Units::Kelvin => temp - KELVIN_OFFSET,
+        }
+    }
+
+    /// Converts a temperature in °C to these units
+    pub fn from_celsius(self, temp_c: f64) -> f64 {
+        match self {
+            Units::Metric => temp_c,
+            Units::Imperial => temp_c * 9.0 / 5.0 + 32.0,
+            Units::Kelvin => temp_c + KELVIN_OFFSET,
+        }
+    }
+
+    /// Converts a wind speed in these units to km/h
+    pub fn to_kph(self, speed: f64) -> f64 {
+        match self {
+            Units::Metric | Units::Kelvin => speed,
+            Units::Imperial => speed * KPH_PER_MPH,
+        }
+    }
+
+    /// Converts a wind speed in km/h to these units
+    pub fn from_kph(self, speed_kph: f64) -> f64 {
+        match self {
+            Units::Metric | Units::Kelvin => speed_kph,
+            Units::Imperial => speed_kph / KPH_PER_MPH,
+        }
+    }
+}
+
+/// Units chosen per field, so temperature and wind can use different systems
+///
+/// `From<Units>` gives the all-or-nothing choice most callers want.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnitPrefs {
+    pub temp: Units,
+    pub wind: Units,
+}
+
+impl From<Units> for UnitPrefs {
+    fn from(units: Units) -> Self {
+        UnitPrefs { temp: units, wind: units }
+    }
+}
+
+/// A whole-number percentage, 0-100: humidity, chance of rain
+///
+/// The same idea as cp7's `Snowball(i64)`: wrapping the `u8` stops a stray 200
+/// getting in, and stops a percentage being passed where some other number
+/// belongs. Derefs to the `u8` for arithmetic, and serializes as the bare number.
+/// ```
+/// # use weather_dashboard::Percentage;
+/// let humidity = Percentage::new(82).unwrap();
+/// assert_eq!(*humidity, 82);
+/// assert_eq!(humidity.to_string(), "82%");
+/// assert_eq!(format!("[{:>4}]", humidity), "[ 82%]");
+///
+/// assert!(Percentage::new(100).is_ok());
+/// assert_eq!(Percentage::new(101).unwrap_err().to_string(), "101 is not a percentage (0-100)");
+/// assert_eq!(*Percentage::clamped(250), 100);
+///
+/// assert_eq!(serde_json::to_string(&humidity).unwrap(), "82");
+/// assert!(serde_json::from_str::<Percentage>("150").is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Percentage(u8);
+
+/// Returned by `Percentage::new` for a value above 100
+#[derive(Debug, Error)]
+#[error("{0} is not a percentage (0-100)")]
+pub struct OutOfRange(pub u8);
+
+impl Percentage {
+    /// Checked: `value` must be 0-100
+    pub fn new(value: u8) -> Result<Self, OutOfRange> {
+        if value > 100 {
+            return Err(OutOfRange(value));
+        }
+        Ok(Percentage(value))
+    }
+
+    /// Anything above 100 becomes 100
+    pub fn clamped(value: u8) -> Self {
+        Percentage(value.min(100))
+    }
+}
+
+impl TryFrom<u8> for Percentage {
+    type Error = OutOfRange;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Percentage::new(value)
+    }
+}
+
+impl From<Percentage> for u8 {
+    fn from(percentage: Percentage) -> u8 {
+        percentage.0
+    }
+}
+
+impl Deref for Percentage {
+    type Target = u8;
+
+    fn deref(&self) -> &u8 {
+        &self.0
+    }
+}
+
+impl fmt::Display for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // pad, not write!, so widths like {:>4} apply to the whole "82%"
+        f.pad(&format!("{}%", self.0))
+    }
+}
 
 /// Our unified weather data structure
 /// This is what we'll display to the user
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// Serializes to a flat JSON object and reads back unchanged:
+/// ```
+/// # use weather_dashboard::models::Reading;
+/// # use weather_dashboard::WeatherData;
+/// let weather = WeatherData::new(Reading {
+///     location: "London, United Kingdom".to_string(),
+///     temperature: 11.0,
+///     feels_like: 9.5,
+///     humidity: 82,
+///     description: "Light rain".to_string(),
+///     wind_speed: 14.4,
+///     wind_gust: Some(22.3),
+///     source: "WeatherAPI.com - London, United Kingdom".to_string(),
+///     last_updated_epoch: 1_700_000_100,
+/// })
+/// .unwrap();
+/// let json = serde_json::to_string(&weather).unwrap();
+/// assert_eq!(serde_json::from_str::<WeatherData>(&json).unwrap(), weather);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WeatherData {
+    pub location: String,
     pub temperature: f64,
     pub feels_like: f64,
+    pub humidity: Percentage,
+    pub description: String,
+    pub wind_speed: f64,
+    /// Peak gust speed, in the same units as `wind_speed` - `None` if the provider didn't report one
+    #[serde(default)]
+    pub wind_gust: Option<f64>,
+    pub source: String,
+    /// When the station took the reading, in seconds since the Unix epoch (0 if unknown)
+    #[serde(default)]
+    pub last_updated_epoch: i64,
+    /// The reading time as local "HH:MM", empty if unknown
+    #[serde(default)]
+    pub observed_at: String,
+}
+
+/// The raw values of one reading, before `WeatherData::new` checks them
+///
+/// Named fields instead of nine positional arguments, so two `f64`s can't
+/// trade places unnoticed. `observed_at` isn't here: it's derived from
+/// `last_updated_epoch`. `Default` leaves out what a caller doesn't know.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Reading {
+    pub location: String,
+    pub temperature: f64,
+    pub feels_like: f64,
+    /// Checked to be 0-100
     pub humidity: u8,
     pub description: String,
+    /// Checked not to be negative
     pub wind_speed: f64,
+    /// Checked not to be negative
+    pub wind_gust: Option<f64>,
     pub source: String,
+    pub last_updated_epoch: i64,
+}
+
+impl WeatherData {
+    /// Builds a reading, rejecting values no real weather can have
+    ///
+    /// Humidity must be 0-100, and wind speed and gusts can't be negative.
+    /// `observed_at` is filled in from `last_updated_epoch` in local time.
+    /// ```
+    /// # use weather_dashboard::models::Reading;
+    /// # use weather_dashboard::WeatherData;
+    /// let weather = WeatherData::new(Reading {
+    ///     location: "Oslo, Norway".to_string(),
+    ///     humidity: 70,
+    ///     wind_speed: 18.0,
+    ///     ..Reading::default()
+    /// })
+    /// .unwrap();
+    /// assert_eq!(*weather.humidity, 70);
+    /// assert_eq!(weather.observed_at, "");
+    /// ```
+    pub fn new(reading: Reading) -> Result<Self, WeatherError> {
+        let humidity = Percentage::new(reading.humidity)
+            .map_err(|e| WeatherError::InvalidReading(format!("humidity {}", e)))?;
+        if reading.wind_speed < 0.0 {
+            return Err(WeatherError::InvalidReading(format!("wind speed {} is negative", reading.wind_speed)));
+        }
+        if let Some(gust) = reading.wind_gust
+            && gust < 0.0
+        {
+            return Err(WeatherError::InvalidReading(format!("wind gust {} is negative", gust)));
+        }
+
+        Ok(WeatherData {
+            location: reading.location,
+            temperature: reading.temperature,
+            feels_like: reading.feels_like,
+            humidity,
+            description: reading.description,
+            wind_speed: reading.wind_speed,
+            wind_gust: reading.wind_gust,
+            source: reading.source,
+            last_updated_epoch: reading.last_updated_epoch,
+            observed_at: observed_at(reading.last_updated_epoch, &chrono::Local),
+        })
+    }
+
+    /// Converts a parsed `/current.json` response, picking values for `units`
+    ///
+    /// Pure - no network - so the mapping can be checked on a hand-built response.
+    /// Temperature and wind are chosen independently when given `UnitPrefs`.
+    /// Gusts follow the wind units, and stay `None` when the response has none.
+    /// Goes through `WeatherData::new`, so an impossible reading is an error.
+    ///
+    /// ```
+    /// # use weather_dashboard::{Units, WeatherData};
+    /// # use weather_dashboard::models::WeatherApiResponse;
+    /// let json = r#"{
+    ///     "location": {"name": "Oslo", "country": "Norway"},
+    ///     "current": {"temp_c": 5.0, "temp_f": 41.0, "feelslike_c": 2.0, "feelslike_f": 35.6,
+    ///                 "humidity": 70, "wind_kph": 18.0, "wind_mph": 11.2,
+    ///                 "gust_kph": 29.5, "gust_mph": 18.3,
+    ///                 "condition": {"text": "Cloudy"}}
+    /// }"#;
+    /// let parse = |json: &str| serde_json::from_str::<WeatherApiResponse>(json).unwrap();
+    ///
+    /// let metric = WeatherData::from_api_response(parse(json), Units::Metric).unwrap();
+    /// assert_eq!((metric.temperature, metric.wind_speed, metric.wind_gust), (5.0, 18.0, Some(29.5)));
+    ///
+    /// let imperial = WeatherData::from_api_response(parse(json), Units::Imperial).unwrap();
+    /// assert_eq!((imperial.temperature, imperial.wind_speed, imperial.wind_gust), (41.0, 11.2, Some(18.3)));
+    /// assert_eq!(imperial.location, "Oslo, Norway");
+    ///
+    /// let calm = json.replace(r#""gust_kph": 29.5, "gust_mph": 18.3,"#, "");
+    /// let calm = WeatherData::from_api_response(parse(&calm), Units::Imperial).unwrap();
+    /// assert_eq!(calm.wind_gust, None);
+    /// ```
+    pub fn from_api_response(response: WeatherApiResponse, units: impl Into<UnitPrefs>) -> Result<Self, WeatherError> {
+        let units = units.into();
+        let current = response.current;
+
+        let (temperature, feels_like) = match units.temp {
+            Units::Imperial => (current.temp_f, current.feelslike_f),
+            Units::Metric => (current.temp_c, current.feelslike_c),
+            Units::Kelvin => (current.temp_c + KELVIN_OFFSET, current.feelslike_c + KELVIN_OFFSET),
+        };
+        let (wind_speed, wind_gust) = match units.wind {
+            Units::Imperial => (current.wind_mph, current.gust_mph),
+            Units::Metric | Units::Kelvin => (current.wind_kph, current.gust_kph),
+        };
+
+        WeatherData::new(Reading {
+            location: format!("{}, {}", response.location.name, response.location.country),
+            temperature,
+            feels_like,
+            humidity: *current.humidity,
+            description: current.condition.text,
+            wind_speed,
+            wind_gust,
+            source: format!("WeatherAPI.com - {}, {}", response.location.name, response.location.country),
+            last_updated_epoch: current.last_updated_epoch,
+        })
+    }
+
+    /// How much warmer (positive) or colder (negative) it feels than it is
+    ///
+    /// Wind chill pulls this below zero, humidity pushes it above.
+    /// ```
+    /// # use weather_dashboard::models::Reading;
+    /// # use weather_dashboard::WeatherData;
+    /// let weather = |temperature, feels_like| {
+    ///     WeatherData::new(Reading {
+    ///         location: "Oslo, Norway".to_string(),
+    ///         temperature,
+    ///         feels_like,
+    ///         humidity: 70,
+    ///         description: "Cloudy".to_string(),
+    ///         wind_speed: 18.0,
+    ///         wind_gust: Some(25.0),
+    ///         source: "WeatherAPI.com".to_string(),
+    ///         ..Reading::default()
+    ///     })
+    ///     .unwrap()
+    /// };
+    /// assert_eq!(weather(11.0, 8.0).feels_like_delta(), -3.0);
+    /// assert_eq!(weather(30.0, 32.5).feels_like_delta(), 2.5);
+    /// assert_eq!(weather(20.0, 20.0).feels_like_delta(), 0.0);
+    /// ```
+    pub fn feels_like_delta(&self) -> f64 {
+        self.feels_like - self.temperature
+    }
+}
+
+/// What `sort_results` orders cities by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortField {
+    Temp,
+    Humidity,
+    Wind,
+}
+
+/// A sort field name that `SortField::from_str` doesn't recognise
+#[derive(Debug, Error)]
+#[error("unknown sort field '{0}' (expected temp, humidity or wind)")]
+pub struct UnknownSortField(String);
+
+impl FromStr for SortField {
+    type Err = UnknownSortField;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "temp" | "temperature" => Ok(SortField::Temp),
+            "humidity" => Ok(SortField::Humidity),
+            "wind" => Ok(SortField::Wind),
+            _ => Err(UnknownSortField(s.to_string())),
+        }
+    }
+}
+
+impl SortField {
+    /// The value of this field for one reading
+    pub fn value(self, weather: &WeatherData) -> f64 {
+        match self {
+            SortField::Temp => weather.temperature,
+            SortField::Humidity => f64::from(*weather.humidity),
+            SortField::Wind => weather.wind_speed,
+        }
+    }
+}
+
+/// One value of a report, for `--field`: temp, feels_like, humidity, wind or condition
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReportField {
+    Temp,
+    FeelsLike,
+    Humidity,
+    Wind,
+    Condition,
+}
+
+/// A field name that `ReportField::from_str` doesn't recognise
+#[derive(Debug, Error)]
+#[error("unknown field '{0}' (expected temp, feels_like, humidity, wind or condition)")]
+pub struct UnknownReportField(String);
+
+impl FromStr for ReportField {
+    type Err = UnknownReportField;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "temp" | "temperature" => Ok(ReportField::Temp),
+            "feels_like" | "feels-like" => Ok(ReportField::FeelsLike),
+            "humidity" => Ok(ReportField::Humidity),
+            "wind" => Ok(ReportField::Wind),
+            "condition" | "conditions" => Ok(ReportField::Condition),
+            _ => Err(UnknownReportField(s.to_string())),
+        }
+    }
+}
+
+/// Sorts per-city results by `field`, ascending unless `descending`
+///
+/// Failed fetches always go last, and NaN readings just before them, whatever
+/// the direction. The sort is stable, so ties keep the order the cities were given in.
+/// ```
+/// # use weather_dashboard::WeatherData;
+/// # use weather_dashboard::models::{sort_results, Reading, SortField};
+/// let weather = |temperature| {
+///     WeatherData::new(Reading {
+///         temperature,
+///         feels_like: temperature,
+///         humidity: 50,
+///         description: "Clear".to_string(),
+///         wind_speed: 10.0,
+///         wind_gust: Some(10.0),
+///         source: "WeatherAPI.com".to_string(),
+///         ..Reading::default()
+///     })
+///     .unwrap()
+/// };
+/// let mut results = vec![
+///     ("Tokyo".to_string(), Ok(weather(18.5))),
+///     ("Atlantis".to_string(), Err("city not found")),
+///     ("London".to_string(), Ok(weather(9.0))),
+///     ("Paris".to_string(), Ok(weather(12.0))),
+/// ];
+/// let order = |results: &[(String, Result<WeatherData, &str>)]| {
+///     results.iter().map(|(city, _)| city.clone()).collect::<Vec<_>>()
+/// };
+///
+/// sort_results(&mut results, SortField::Temp, false);
+/// assert_eq!(order(&results), ["London", "Paris", "Tokyo", "Atlantis"]);
+///
+/// sort_results(&mut results, SortField::Temp, true);
+/// assert_eq!(order(&results), ["Tokyo", "Paris", "London", "Atlantis"]);
+/// ```
+pub fn sort_results<E>(results: &mut [(String, Result<WeatherData, E>)], field: SortField, descending: bool) {
+    use std::cmp::Ordering;
+
+    // None for failures and NaN, which both stay at the end in either direction
+    let key = |result: &Result<WeatherData, E>| {
+        result.as_ref().ok().map(|w| field.value(w)).filter(|value| !value.is_nan())
+    };
+    let rank = |result: &Result<WeatherData, E>| match result {
+        Ok(_) => 0,
+        Err(_) => 1,
+    };
+
+    results.sort_by(|(_, a), (_, b)| match (key(a), key(b)) {
+        (Some(a), Some(b)) => {
+            let order = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+            if descending { order.reverse() } else { order }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => rank(a).cmp(&rank(b)),
+    });
 }
 
 /// WeatherAPI.com response structure
 /// This matches their JSON format exactly
+///
+/// Fields we don't use are ignored:
+/// ```
+/// # use weather_dashboard::models::WeatherApiResponse;
+/// let json = r#"{
+///     "location": {"name": "London", "region": "City of London", "country": "United Kingdom"},
+///     "current": {
+///         "temp_c": 11.0, "temp_f": 51.8, "feelslike_c": 9.5, "feelslike_f": 49.1,
+///         "humidity": 82, "wind_kph": 14.4, "wind_mph": 8.9, "uv": 1.0,
+///         "condition": {"text": "Light rain", "code": 1183}
+///     }
+/// }"#;
+/// let response: WeatherApiResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.location.name, "London");
+/// assert_eq!(*response.current.humidity, 82);
+/// assert_eq!(response.current.condition.code, 1183);
+///
+/// // A missing required field is reported by name
+/// let err = serde_json::from_str::<WeatherApiResponse>(r#"{"location": {"name": "London"}}"#).unwrap_err();
+/// assert!(err.to_string().contains("missing field `country`"));
+///
+/// // So is a humidity outside 0-100
+/// let bad = json.replace(r#""humidity": 82"#, r#""humidity": 150"#);
+/// let err = serde_json::from_str::<WeatherApiResponse>(&bad).unwrap_err();
+/// assert!(err.to_string().contains("humidity 150 is not a percentage"));
+/// ```
 #[derive(Debug, Deserialize)]
 pub struct WeatherApiResponse {
     pub location: Location,
@@ -26,19 +568,342 @@ pub struct Location {
     pub country: String,
 }
 
+/// The `current` block of a `/current.json` response
+///
+/// Only the temperatures, humidity, condition and wind are required. Fields
+/// the API added later (gusts, the observation time) default to `None`/0, so
+/// a minimal or older response still parses.
 #[derive(Debug, Deserialize)]
 pub struct Current {
     pub temp_c: f64,
     pub temp_f: f64,
     pub feelslike_c: f64,
     pub feelslike_f: f64,
-    pub humidity: u8,
+    #[serde(deserialize_with = "humidity")]
+    pub humidity: Percentage,
     pub condition: Condition,
     pub wind_kph: f64,
     pub wind_mph: f64,
+    #[serde(default)]
+    pub gust_kph: Option<f64>,
+    #[serde(default)]
+    pub gust_mph: Option<f64>,
+    #[serde(default)]
+    pub last_updated_epoch: i64,
+}
+
+impl Current {
+    /// Parses a `current` block on its own, e.g. from a minimal provider response
+    ///
+    /// ```
+    /// # use weather_dashboard::models::Current;
+    /// let current = Current::parse(r#"{
+    ///     "temp_c": 5.0, "temp_f": 41.0, "feelslike_c": 2.0, "feelslike_f": 35.6,
+    ///     "humidity": 70, "wind_kph": 18.0, "wind_mph": 11.2,
+    ///     "condition": {"text": "Cloudy"}
+    /// }"#)
+    /// .unwrap();
+    /// assert_eq!((current.gust_kph, current.gust_mph), (None, None));
+    /// assert_eq!((current.last_updated_epoch, current.condition.code), (0, 0));
+    ///
+    /// // The core fields are still required
+    /// assert!(Current::parse(r#"{"temp_c": 5.0}"#).is_err());
+    /// ```
+    pub fn parse(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Condition {
     pub text: String,
+    /// WeatherAPI.com condition code, e.g. 1000 = sunny (see their conditions list)
+    #[serde(default)]
+    pub code: u32,
+}
+
+/// One day of a multi-day forecast, already converted to the chosen units
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ForecastDay {
+    pub date: String,
+    pub max_temp: f64,
+    pub min_temp: f64,
+    pub description: String,
+    pub condition_code: u32,
+    /// Chance of rain during the day
+    pub chance_of_rain: Percentage,
+    pub moon_phase: String,
+}
+
+impl ForecastDay {
+    /// Converts one day of a `/forecast.json` or `/history.json` response, picking values for `units`
+    pub fn from_api_day(day: ApiForecastDay, units: Units) -> Self {
+        let (max_temp, min_temp) = match units {
+            Units::Imperial => (day.day.maxtemp_f, day.day.mintemp_f),
+            Units::Metric => (day.day.maxtemp_c, day.day.mintemp_c),
+            Units::Kelvin => (day.day.maxtemp_c + KELVIN_OFFSET, day.day.mintemp_c + KELVIN_OFFSET),
+        };
+
+        ForecastDay {
+            date: day.date,
+            max_temp,
+            min_temp,
+            description: day.day.condition.text,
+            condition_code: day.day.condition.code,
+            chance_of_rain: day.day.daily_chance_of_rain,
+            moon_phase: day.astro.moon_phase,
+        }
+    }
+}
+
+/// Formats an observation time as "HH:MM" in `tz`, or "" for an unknown (0) epoch
+///
+/// ```
+/// # use weather_dashboard::models::observed_at;
+/// assert_eq!(observed_at(1_700_000_100, &chrono::Utc), "22:15");
+/// assert_eq!(observed_at(0, &chrono::Utc), "");
+/// ```
+pub fn observed_at<Tz: TimeZone>(epoch: i64, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match DateTime::from_timestamp(epoch, 0) {
+        Some(time) if epoch > 0 => time.with_timezone(tz).format("%H:%M").to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Reads a relative humidity, rejecting anything outside 0-100
+///
+/// A plain `u8` would happily accept 200 from a buggy or spoofed response.
+/// `Percentage` would reject it too - this just names the field in the error.
+fn humidity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Percentage, D::Error> {
+    let value = u8::deserialize(deserializer)?;
+    Percentage::new(value).map_err(|e| serde::de::Error::custom(format!("humidity {}", e)))
+}
+
+/// WeatherAPI.com `/forecast.json` response structure - `/history.json` has the same shape
+#[derive(Debug, Deserialize)]
+pub struct ForecastApiResponse {
+    pub location: Location,
+    pub forecast: Forecast,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Forecast {
+    pub forecastday: Vec<ApiForecastDay>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiForecastDay {
+    pub date: String,
+    pub day: Day,
+    pub astro: Astro,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Day {
+    pub maxtemp_c: f64,
+    pub maxtemp_f: f64,
+    pub mintemp_c: f64,
+    pub mintemp_f: f64,
+    /// History days may leave this out - it was a forecast, after all
+    #[serde(default)]
+    pub daily_chance_of_rain: Percentage,
+    pub condition: Condition,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Astro {
+    pub moon_phase: String,
+}
+
+/// Sun and moon times for one place and date, from `/astronomy.json`
+///
+/// Times are local to the place, as WeatherAPI.com formats them ("06:42 AM").
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Astronomy {
+    pub sunrise: String,
+    pub sunset: String,
+    pub moonrise: String,
+    pub moonset: String,
+    pub moon_phase: String,
+}
+
+/// WeatherAPI.com `/astronomy.json` response structure
+#[derive(Debug, Deserialize)]
+pub struct AstronomyApiResponse {
+    pub astronomy: AstronomyWrapper,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AstronomyWrapper {
+    pub astro: Astronomy,
+}
+
+/// One candidate location from WeatherAPI.com's `/search.json` endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CitySuggestion {
+    pub name: String,
+    pub region: String,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// A city name resolved to coordinates through `/search.json`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GeoLocation {
+    pub lat: f64,
+    pub lon: f64,
+    pub resolved_name: String,
+}
+
+impl GeoLocation {
+    /// The `"lat,lon"` query string WeatherAPI.com accepts in place of a city name
+    pub fn query(&self) -> String {
+        format!("{},{}", self.lat, self.lon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A complete `/current.json?q=London&aqi=no` response, as WeatherAPI.com sends it
+    const CURRENT_FIXTURE: &str = r#"{
+    "location": {
+        "name": "London",
+        "region": "City of London, Greater London",
+        "country": "United Kingdom",
+        "lat": 51.5171,
+        "lon": -0.1062,
+        "tz_id": "Europe/London",
+        "localtime_epoch": 1700000342,
+        "localtime": "2023-11-14 22:19"
+    },
+    "current": {
+        "last_updated_epoch": 1700000100,
+        "last_updated": "2023-11-14 22:15",
+        "temp_c": 11.0,
+        "temp_f": 51.8,
+        "is_day": 0,
+        "condition": {
+            "text": "Light rain",
+            "icon": "//cdn.weatherapi.com/weather/64x64/night/296.png",
+            "code": 1183
+        },
+        "wind_mph": 8.9,
+        "wind_kph": 14.4,
+        "wind_degree": 220,
+        "wind_dir": "SW",
+        "pressure_mb": 998.0,
+        "pressure_in": 29.47,
+        "precip_mm": 0.3,
+        "precip_in": 0.01,
+        "humidity": 82,
+        "cloud": 75,
+        "feelslike_c": 9.5,
+        "feelslike_f": 49.1,
+        "vis_km": 10.0,
+        "vis_miles": 6.0,
+        "uv": 1.0,
+        "gust_mph": 13.9,
+        "gust_kph": 22.3
+    }
+}"#;
+
+    fn parse(json: &str) -> serde_json::Result<WeatherApiResponse> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn the_full_response_parses_field_by_field() {
+        let response = parse(CURRENT_FIXTURE).unwrap();
+        assert_eq!(response.location.name, "London");
+        assert_eq!(response.location.country, "United Kingdom");
+
+        let current = response.current;
+        assert_eq!((current.temp_c, current.temp_f), (11.0, 51.8));
+        assert_eq!((current.feelslike_c, current.feelslike_f), (9.5, 49.1));
+        assert_eq!(*current.humidity, 82);
+        assert_eq!((current.wind_kph, current.wind_mph), (14.4, 8.9));
+        assert_eq!((current.gust_kph, current.gust_mph), (Some(22.3), Some(13.9)));
+        assert_eq!(current.last_updated_epoch, 1_700_000_100);
+        assert_eq!(current.condition.text, "Light rain");
+        assert_eq!(current.condition.code, 1183);
+    }
+
+    #[test]
+    fn the_full_response_converts_to_weather_data() {
+        let weather = WeatherData::from_api_response(parse(CURRENT_FIXTURE).unwrap(), Units::Metric).unwrap();
+        assert_eq!(weather.location, "London, United Kingdom");
+        assert_eq!(weather.source, "WeatherAPI.com - London, United Kingdom");
+        assert_eq!((weather.temperature, weather.feels_like), (11.0, 9.5));
+        assert_eq!(*weather.humidity, 82);
+        assert_eq!(weather.description, "Light rain");
+        assert_eq!((weather.wind_speed, weather.wind_gust), (14.4, Some(22.3)));
+        assert_eq!(weather.last_updated_epoch, 1_700_000_100);
+        assert_eq!(weather.observed_at, observed_at(1_700_000_100, &chrono::Local));
+    }
+
+    #[test]
+    fn weather_data_survives_a_json_round_trip() {
+        let weather = WeatherData::from_api_response(parse(CURRENT_FIXTURE).unwrap(), Units::Imperial).unwrap();
+        let json = serde_json::to_string(&weather).unwrap();
+        assert_eq!(serde_json::from_str::<WeatherData>(&json).unwrap(), weather);
+    }
+
+    fn oslo(humidity: u8, wind_speed: f64, wind_gust: Option<f64>) -> Reading {
+        Reading {
+            location: "Oslo, Norway".to_string(),
+            temperature: 5.0,
+            feels_like: 2.0,
+            humidity,
+            description: "Cloudy".to_string(),
+            wind_speed,
+            wind_gust,
+            source: "test".to_string(),
+            last_updated_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn new_accepts_the_edges_of_the_valid_ranges() {
+        let weather = WeatherData::new(oslo(100, 0.0, Some(0.0))).unwrap();
+        assert_eq!((*weather.humidity, weather.wind_speed, weather.wind_gust), (100, 0.0, Some(0.0)));
+        assert!(WeatherData::new(oslo(0, 18.0, None)).is_ok());
+    }
+
+    #[test]
+    fn new_rejects_a_negative_wind_speed() {
+        let error = WeatherData::new(oslo(70, -3.0, None)).unwrap_err();
+        assert!(matches!(error, WeatherError::InvalidReading(_)));
+        assert_eq!(error.to_string(), "Invalid weather reading: wind speed -3 is negative");
+    }
+
+    #[test]
+    fn new_rejects_a_negative_gust() {
+        let error = WeatherData::new(oslo(70, 18.0, Some(-0.5))).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid weather reading: wind gust -0.5 is negative");
+    }
+
+    #[test]
+    fn new_rejects_humidity_above_100() {
+        let error = WeatherData::new(oslo(150, 18.0, None)).unwrap_err();
+        assert!(matches!(error, WeatherError::InvalidReading(_)));
+        assert_eq!(error.to_string(), "Invalid weather reading: humidity 150 is not a percentage (0-100)");
+    }
+
+    #[test]
+    fn malformed_input_is_a_serde_json_error() {
+        let truncated = &CURRENT_FIXTURE[..CURRENT_FIXTURE.len() / 2];
+        let error: serde_json::Error = parse(truncated).unwrap_err();
+        assert!(error.is_eof());
+
+        let wrong_type = CURRENT_FIXTURE.replace(r#""temp_c": 11.0"#, r#""temp_c": "eleven""#);
+        let error: serde_json::Error = parse(&wrong_type).unwrap_err();
+        assert!(error.is_data());
+        assert!(error.to_string().contains("invalid type"));
+    }
 }
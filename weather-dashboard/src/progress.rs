@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use stdio_learning::io_harness::progress_bar;
+
+/// Frames for an indeterminate spinner, shown while some download's total size is unknown
+pub const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Download progress of several forecasts at once, drawn as a single line
+///
+/// Every city's callback updates its own entry and redraws the whole line,
+/// so concurrent downloads no longer overwrite each other's `\r` frames.
+/// The bar is the stdio buffering example's; until every download has
+/// reported a `Content-Length` a spinner with the byte count stands in.
+/// ```
+/// # use weather_dashboard::progress::DownloadProgress;
+/// let mut progress = DownloadProgress::new(["Oslo", "Lima"]);
+/// let mut out = Vec::new();
+///
+/// progress.update(&mut out, "Oslo", 500, Some(1000)).unwrap();
+/// assert_eq!(String::from_utf8(out.split_off(0)).unwrap(), "\r/ 500 bytes (1/2)\x1b[K");
+///
+/// progress.update(&mut out, "Lima", 0, Some(1000)).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "\r[#####---------------]  25% (2/2)\x1b[K");
+/// ```
+pub struct DownloadProgress {
+    /// (bytes received, total if known) per city - `None` received means not started yet
+    downloads: HashMap<String, (Option<u64>, Option<u64>)>,
+    frame: usize,
+}
+
+impl DownloadProgress {
+    /// Tracks one download per city in `cities`, none of them started
+    pub fn new<S: Into<String>>(cities: impl IntoIterator<Item = S>) -> Self {
+        let downloads = cities.into_iter().map(|city| (city.into(), (None, None))).collect();
+        DownloadProgress { downloads, frame: 0 }
+    }
+
+    /// Records `city`'s progress and redraws the combined line on `out`
+    ///
+    /// The line starts with `\r` and ends by clearing whatever a longer
+    /// previous frame left behind, so it always overwrites itself cleanly.
+    pub fn update<W: Write>(&mut self, out: &mut W, city: &str, received: u64, total: Option<u64>) -> io::Result<()> {
+        self.downloads.insert(city.to_string(), (Some(received), total));
+
+        let started = self.downloads.values().filter(|(received, _)| received.is_some()).count();
+        let received: u64 = self.downloads.values().filter_map(|(received, _)| *received).sum();
+        // Only a total for every city gives a meaningful percentage
+        let total: Option<u64> = self.downloads.values().map(|(_, total)| *total).sum();
+
+        match total {
+            Some(total) if total > 0 => progress_bar(out, (received * 100 / total).min(100) as u8)?,
+            _ => {
+                self.frame += 1;
+                write!(out, "\r{} {} bytes", SPINNER[self.frame % SPINNER.len()], received)?;
+            }
+        }
+        write!(out, " ({}/{})\x1b[K", started, self.downloads.len())?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(progress: &mut DownloadProgress, city: &str, received: u64, total: Option<u64>) -> String {
+        let mut out = Vec::new();
+        progress.update(&mut out, city, received, total).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn one_city_draws_the_plain_bar() {
+        let mut progress = DownloadProgress::new(["Oslo"]);
+        assert_eq!(frame(&mut progress, "Oslo", 0, Some(2000)), "\r[--------------------]   0% (1/1)\x1b[K");
+        assert_eq!(frame(&mut progress, "Oslo", 2000, Some(2000)), "\r[####################] 100% (1/1)\x1b[K");
+    }
+
+    #[test]
+    fn several_cities_share_one_line() {
+        let mut progress = DownloadProgress::new(["Oslo", "Lima", "Pune"]);
+        frame(&mut progress, "Oslo", 1000, Some(1000));
+        frame(&mut progress, "Lima", 0, Some(1000));
+        // Pune hasn't started, so there's no overall total yet
+        assert!(frame(&mut progress, "Lima", 500, Some(1000)).ends_with(" 1500 bytes (2/3)\x1b[K"));
+
+        let line = frame(&mut progress, "Pune", 500, Some(2000));
+        assert_eq!(line, "\r[##########----------]  50% (3/3)\x1b[K");
+        // One frame, one line: nothing after the clear, and no newline
+        assert_eq!(line.matches('\r').count(), 1);
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn a_missing_content_length_keeps_the_spinner_going() {
+        let mut progress = DownloadProgress::new(["Oslo", "Lima"]);
+        frame(&mut progress, "Oslo", 100, Some(100));
+        let first = frame(&mut progress, "Lima", 10, None);
+        let second = frame(&mut progress, "Lima", 20, None);
+        assert_eq!(first, "\r- 110 bytes (2/2)\x1b[K");
+        assert_eq!(second, "\r\\ 120 bytes (2/2)\x1b[K");
+    }
+}
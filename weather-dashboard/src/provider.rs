@@ -0,0 +1,245 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::Deserialize;
+
+use crate::client::{redact_api_key, WeatherClient};
+use crate::error::WeatherError;
+use crate::models::{Reading, UnitPrefs, WeatherData};
+
+/// The future a `WeatherProvider` hands back - boxed, so the trait works as `dyn WeatherProvider`
+pub type ProviderFuture<'a> = Pin<Box<dyn Future<Output = Result<WeatherData, WeatherError>> + Send + 'a>>;
+
+/// A source of current weather: WeatherAPI.com, OpenWeatherMap, or a chain of them
+pub trait WeatherProvider: Send + Sync {
+    /// Shown in error messages, e.g. "WeatherAPI.com"
+    fn name(&self) -> &str;
+
+    /// Fetches the current weather for `city`, converted to `units`
+    fn fetch_weather<'a>(&'a self, city: &'a str, units: UnitPrefs) -> ProviderFuture<'a>;
+}
+
+impl WeatherProvider for WeatherClient {
+    fn name(&self) -> &str {
+        "WeatherAPI.com"
+    }
+
+    fn fetch_weather<'a>(&'a self, city: &'a str, units: UnitPrefs) -> ProviderFuture<'a> {
+        Box::pin(WeatherClient::fetch_weather(self, city, units))
+    }
+}
+
+/// Name of the environment variable holding the OpenWeatherMap key
+pub const OPENWEATHER_KEY_VAR: &str = "OPENWEATHER_API_KEY";
+
+/// Current weather from OpenWeatherMap's `/data/2.5/weather` endpoint
+#[derive(Clone)]
+pub struct OpenWeatherMapClient {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+impl OpenWeatherMapClient {
+    /// Creates a client with the given OpenWeatherMap API key
+    pub fn new(api_key: String) -> Self {
+        OpenWeatherMapClient {
+            client: reqwest::Client::new(),
+            api_key,
+        }
+    }
+
+    /// Reads the key from `OPENWEATHER_API_KEY`, or `None` if it's unset or blank
+    pub fn from_env() -> Option<Self> {
+        let key = std::env::var(OPENWEATHER_KEY_VAR).ok()?;
+        let key = key.trim();
+        (!key.is_empty()).then(|| Self::new(key.to_string()))
+    }
+
+    async fn fetch(&self, city: &str, units: UnitPrefs) -> Result<WeatherData, WeatherError> {
+        // Always metric: °C and m/s, converted to `units` below
+        let url = format!(
+            "https://api.openweathermap.org/data/2.5/weather?q={}&appid={}&units=metric",
+            city, self.api_key
+        );
+        let http_response = self.client.get(&url).send().await.map_err(|e| e.without_url())?;
+
+        let status = http_response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(WeatherError::CityNotFound(city.to_string()));
+        }
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(WeatherError::RateLimited(None));
+        }
+        if !status.is_success() {
+            let error_text = redact_api_key(&http_response.text().await?);
+            return Err(WeatherError::ApiError(format!("API returned status {}: {}", status, error_text)));
+        }
+
+        let response = http_response.json::<OwmResponse>().await?;
+        response.into_weather(units)
+    }
+}
+
+impl WeatherProvider for OpenWeatherMapClient {
+    fn name(&self) -> &str {
+        "OpenWeatherMap"
+    }
+
+    fn fetch_weather<'a>(&'a self, city: &'a str, units: UnitPrefs) -> ProviderFuture<'a> {
+        Box::pin(self.fetch(city, units))
+    }
+}
+
+/// OpenWeatherMap `/data/2.5/weather` response, requested with `units=metric`
+#[derive(Debug, Deserialize)]
+pub struct OwmResponse {
+    pub name: String,
+    pub main: OwmMain,
+    pub weather: Vec<OwmCondition>,
+    pub wind: OwmWind,
+    #[serde(default)]
+    pub sys: OwmSys,
+    /// Observation time, seconds since the Unix epoch
+    #[serde(default)]
+    pub dt: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OwmMain {
+    pub temp: f64,
+    pub feels_like: f64,
+    pub humidity: u8,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OwmCondition {
+    pub description: String,
+}
+
+/// Wind in m/s (with `units=metric`)
+#[derive(Debug, Deserialize)]
+pub struct OwmWind {
+    pub speed: f64,
+    #[serde(default)]
+    pub gust: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct OwmSys {
+    #[serde(default)]
+    pub country: String,
+}
+
+impl OwmResponse {
+    /// Converts to our `WeatherData`: °C and m/s become `units`
+    ///
+    /// Fails like `WeatherData::new` on an impossible reading.
+    ///
+    /// ```
+    /// # use weather_dashboard::provider::OwmResponse;
+    /// # use weather_dashboard::Units;
+    /// let json = r#"{
+    ///     "name": "London", "sys": {"country": "GB"}, "dt": 0,
+    ///     "main": {"temp": 10.0, "feels_like": 8.0, "humidity": 81},
+    ///     "weather": [{"description": "light rain"}],
+    ///     "wind": {"speed": 5.0}
+    /// }"#;
+    /// let response: OwmResponse = serde_json::from_str(json).unwrap();
+    /// let weather = response.into_weather(Units::Metric.into()).unwrap();
+    /// assert_eq!(weather.location, "London, GB");
+    /// assert_eq!((weather.temperature, *weather.humidity), (10.0, 81));
+    /// assert_eq!(weather.wind_speed, 18.0); // 5 m/s = 18 km/h
+    /// assert_eq!(weather.wind_gust, None);
+    /// ```
+    pub fn into_weather(self, units: UnitPrefs) -> Result<WeatherData, WeatherError> {
+        let kph = |mps: f64| mps * 3.6;
+        let location = format!("{}, {}", self.name, self.sys.country);
+        WeatherData::new(Reading {
+            location: location.clone(),
+            temperature: units.temp.from_celsius(self.main.temp),
+            feels_like: units.temp.from_celsius(self.main.feels_like),
+            humidity: self.main.humidity,
+            description: self.weather.into_iter().next().map(|c| c.description).unwrap_or_default(),
+            wind_speed: units.wind.from_kph(kph(self.wind.speed)),
+            wind_gust: self.wind.gust.map(|gust| units.wind.from_kph(kph(gust))),
+            source: format!("OpenWeatherMap - {}", location),
+            last_updated_epoch: self.dt,
+        })
+    }
+}
+
+/// Tries each provider in order and returns the first success
+///
+/// If every provider fails, the error lists what each one said. Providers
+/// are tried one after another, never at the same time, so a later one is
+/// only called when the earlier ones have already failed.
+/// ```
+/// # use weather_dashboard::provider::{FallbackProvider, ProviderFuture, WeatherProvider};
+/// # use weather_dashboard::models::Reading;
+/// # use weather_dashboard::{UnitPrefs, Units, WeatherData, WeatherError};
+/// struct Down;
+/// impl WeatherProvider for Down {
+///     fn name(&self) -> &str { "Down" }
+///     fn fetch_weather<'a>(&'a self, _: &'a str, _: UnitPrefs) -> ProviderFuture<'a> {
+///         Box::pin(async { Err(WeatherError::RateLimited(None)) })
+///     }
+/// }
+///
+/// struct Up;
+/// impl WeatherProvider for Up {
+///     fn name(&self) -> &str { "Up" }
+///     fn fetch_weather<'a>(&'a self, city: &'a str, _: UnitPrefs) -> ProviderFuture<'a> {
+///         Box::pin(async move {
+///             WeatherData::new(Reading {
+///                 location: city.to_string(),
+///                 temperature: 21.0,
+///                 feels_like: 21.0,
+///                 humidity: 50,
+///                 description: "Sunny".to_string(),
+///                 wind_speed: 5.0,
+///                 source: "Up".to_string(),
+///                 ..Reading::default()
+///             })
+///         })
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let chain = FallbackProvider::new(vec![Box::new(Down), Box::new(Up)]);
+/// let weather = chain.fetch_weather("Paris", Units::Metric.into()).await.unwrap();
+/// assert_eq!(weather.source, "Up");
+///
+/// let all_down = FallbackProvider::new(vec![Box::new(Down), Box::new(Down)]);
+/// let error = all_down.fetch_weather("Paris", Units::Metric.into()).await.unwrap_err();
+/// assert_eq!(error.to_string(), "Every provider failed: Down: Rate limited by the API; Down: Rate limited by the API");
+/// # });
+/// ```
+pub struct FallbackProvider {
+    providers: Vec<Box<dyn WeatherProvider>>,
+}
+
+impl FallbackProvider {
+    /// A chain trying `providers` first to last
+    pub fn new(providers: Vec<Box<dyn WeatherProvider>>) -> Self {
+        FallbackProvider { providers }
+    }
+}
+
+impl WeatherProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        "fallback chain"
+    }
+
+    fn fetch_weather<'a>(&'a self, city: &'a str, units: UnitPrefs) -> ProviderFuture<'a> {
+        Box::pin(async move {
+            let mut errors = Vec::new();
+            for provider in &self.providers {
+                match provider.fetch_weather(city, units).await {
+                    Ok(weather) => return Ok(weather),
+                    Err(e) => errors.push(format!("{}: {}", provider.name(), e)),
+                }
+            }
+            Err(WeatherError::AllProvidersFailed(errors))
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+
+/// Token-bucket rate limiter shared by every clone of a `WeatherClient`
+///
+/// The bucket starts full with `max_per_second` tokens. Each request takes one
+/// and a background task puts one back every `1 / max_per_second` seconds,
+/// so bursts are allowed up to the bucket size and then calls wait their turn
+/// instead of tripping the API's 429 responses.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    tokens: Arc<Semaphore>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `max_per_second` requests per second (minimum 1)
+    ///
+    /// Must be called inside a tokio runtime - the refill task is spawned here.
+    pub fn new(max_per_second: u32) -> Self {
+        let capacity = max_per_second.max(1) as usize;
+        let tokens = Arc::new(Semaphore::new(capacity));
+
+        // Hold only a weak reference so the task ends once every clone is dropped
+        let refill: Weak<Semaphore> = Arc::downgrade(&tokens);
+        let period = Duration::from_secs_f64(1.0 / capacity as f64);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.tick().await; // the first tick fires immediately
+            loop {
+                interval.tick().await;
+                let Some(tokens) = refill.upgrade() else { break };
+                if tokens.available_permits() < capacity {
+                    tokens.add_permits(1);
+                }
+            }
+        });
+
+        RateLimiter { tokens }
+    }
+
+    /// Waits until a token is available and takes it
+    pub async fn acquire(&self) {
+        // The semaphore is never closed, so acquire can't fail
+        if let Ok(permit) = self.tokens.acquire().await {
+            // Forget the permit so it only comes back through the refill task
+            permit.forget();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::WeatherClient;
+    use crate::error::WeatherError;
+    use tokio::time::Instant;
+
+    // The clock is paused: it only moves when every task is waiting on a timer,
+    // and then jumps straight to the next one - so the timings below are exact
+    #[tokio::test(start_paused = true)]
+    async fn calls_past_the_burst_are_spaced_one_period_apart() {
+        let limiter = RateLimiter::new(4); // one token back every 250ms
+        let start = Instant::now();
+
+        let mut times = Vec::new();
+        for _ in 0..8 {
+            limiter.acquire().await;
+            times.push(start.elapsed());
+        }
+
+        // The bucket starts full: the first 4 go straight through
+        assert!(times[..4].iter().all(|t| t.is_zero()));
+        // After that each one waits for the next refill
+        for pair in times[3..].windows(2) {
+            let gap = pair[1] - pair[0];
+            assert!(gap.abs_diff(Duration::from_millis(250)) < Duration::from_millis(5), "gap {:?}", gap);
+        }
+    }
+
+    #[tokio::test]
+    async fn a_429_becomes_rate_limited_with_the_retry_after_seconds() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/current.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(429)
+            .with_header("Retry-After", "30")
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let error = client.fetch_raw("London").await.unwrap_err();
+        assert!(matches!(error, WeatherError::RateLimited(Some(30))), "{:?}", error);
+    }
+
+    #[tokio::test]
+    async fn a_429_without_retry_after_has_no_seconds() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/current.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(429)
+            .create_async()
+            .await;
+
+        let client = WeatherClient::new("test-key".to_string()).with_base_url(&server.url());
+        let error = client.fetch_raw("London").await.unwrap_err();
+        assert!(matches!(error, WeatherError::RateLimited(None)), "{:?}", error);
+    }
+}
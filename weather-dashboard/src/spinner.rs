@@ -0,0 +1,50 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+/// Braille frames, drawn one after another in the same spot
+pub const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Time between frames
+pub const TICK: Duration = Duration::from_millis(80);
+
+/// A spinner on stderr, animated by its own tokio task until `stop` is called
+///
+/// Only drawn when stderr is a terminal - redirected to a file or a pipe it
+/// would just be a stream of `\r`s. Must be started inside a tokio runtime.
+pub struct Spinner {
+    task: JoinHandle<()>,
+}
+
+impl Spinner {
+    /// Starts spinning next to `message`, or returns `None` if stderr isn't a terminal
+    pub fn start(message: &str) -> Option<Self> {
+        if !io::stderr().is_terminal() {
+            return None;
+        }
+
+        let message = message.to_string();
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK);
+            for frame in FRAMES.iter().cycle() {
+                interval.tick().await;
+                // stderr is unbuffered, but flush anyway - there's no newline to trigger it
+                let mut stderr = io::stderr().lock();
+                let _ = write!(stderr, "\r{} {}", frame, message);
+                let _ = stderr.flush();
+            }
+        });
+        Some(Spinner { task })
+    }
+
+    /// Stops the animation and clears its line
+    pub async fn stop(self) {
+        // The task only yields at `tick().await`, so it's never cut off mid-frame
+        self.task.abort();
+        let _ = self.task.await;
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[2K");
+        let _ = stderr.flush();
+    }
+}
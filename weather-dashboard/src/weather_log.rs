@@ -0,0 +1,87 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{UnitPrefs, WeatherData};
+
+/// One line of a `--log` file: a reading plus when it was logged, and in what units
+///
+/// The reading's fields are flattened into the same object, so each line
+/// also parses as a plain `WeatherData`. `units` says what its numbers mean:
+/// `"units": {"temp": "imperial", "wind": "imperial"}` for °F and mph.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// When the line was written, RFC 3339 in UTC, e.g. "2024-06-21T14:05:00Z"
+    pub logged_at: String,
+    /// Units of `temperature`/`feels_like` and of `wind_speed`/`wind_gust`
+    pub units: UnitPrefs,
+    #[serde(flatten)]
+    pub weather: WeatherData,
+}
+
+impl LogEntry {
+    /// Stamps `weather`, measured in `units`, with the current time
+    pub fn now(weather: WeatherData, units: UnitPrefs) -> Self {
+        LogEntry {
+            logged_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            units,
+            weather,
+        }
+    }
+}
+
+/// Appends one JSON line per reading (all in `units`) to the file at `path`, creating it if needed
+///
+/// Each line goes out in a single write to a file opened for appending, so
+/// two runs logging at the same time each add whole lines rather than
+/// interleaving their bytes.
+/// ```
+/// # use weather_dashboard::weather_log::{append, LogEntry};
+/// # use weather_dashboard::models::Reading;
+/// # use weather_dashboard::{Units, WeatherData};
+/// let weather = WeatherData::new(Reading {
+///     location: "Lima, Peru".to_string(),
+///     temperature: 12.7,
+///     feels_like: 11.25,
+///     humidity: 80,
+///     description: "Mist".to_string(),
+///     wind_speed: 9.0,
+///     wind_gust: None,
+///     source: "WeatherAPI.com".to_string(),
+///     ..Reading::default()
+/// })
+/// .unwrap();
+/// let path = std::env::temp_dir().join(format!("weather-log-doctest-{}.jsonl", std::process::id()));
+/// # let _ = std::fs::remove_file(&path);
+///
+/// append(&path, Units::Metric.into(), [&weather]).unwrap();
+/// append(&path, Units::Metric.into(), [&weather]).unwrap();
+///
+/// let log = std::fs::read_to_string(&path).unwrap();
+/// assert_eq!(log.lines().count(), 2);
+/// assert!(log.contains(r#""units":{"temp":"metric","wind":"metric"}"#));
+/// for line in log.lines() {
+///     let entry: LogEntry = serde_json::from_str(line).unwrap();
+///     assert_eq!(entry.weather, weather);
+///     assert_eq!(entry.units, Units::Metric.into());
+///     let plain: WeatherData = serde_json::from_str(line).unwrap();
+///     assert_eq!(plain, weather);
+/// }
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn append<'a>(
+    path: &Path,
+    units: UnitPrefs,
+    readings: impl IntoIterator<Item = &'a WeatherData>,
+) -> io::Result<()> {
+    let mut file = OpenOptions::new().append(true).create(true).open(path)?;
+    for weather in readings {
+        let mut line = serde_json::to_string(&LogEntry::now(weather.clone(), units))?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::models::{UnitPrefs, WeatherData};
+
+/// Wind chill only applies at or below this temperature (°C)
+pub const WIND_CHILL_MAX_TEMP_C: f64 = 10.0;
+
+/// ... and above this wind speed (km/h) - calmer air doesn't strip heat away
+pub const WIND_CHILL_MIN_WIND_KPH: f64 = 4.8;
+
+/// The heat index regression is only fitted from 80°F (26.7°C) up
+pub const HEAT_INDEX_MIN_TEMP_F: f64 = 80.0;
+
+/// Wind chill in °C, using the North American (Environment Canada / NWS 2001) formula
+///
+/// Returns `temp_c` unchanged outside the formula's range: above 10°C or
+/// with wind at or below 4.8 km/h.
+/// ```
+/// # use weather_dashboard::weather_math::wind_chill_c;
+/// // Published table values: -10°C at 20 km/h feels like -18°C, -20°C at 30 km/h like -33°C
+/// assert!((wind_chill_c(-10.0, 20.0) - -17.9).abs() < 0.1);
+/// assert!((wind_chill_c(-20.0, 30.0) - -32.6).abs() < 0.1);
+/// // Out of range: warm, or calm
+/// assert_eq!(wind_chill_c(15.0, 30.0), 15.0);
+/// assert_eq!(wind_chill_c(-10.0, 3.0), -10.0);
+/// ```
+pub fn wind_chill_c(temp_c: f64, wind_kph: f64) -> f64 {
+    if temp_c > WIND_CHILL_MAX_TEMP_C || wind_kph <= WIND_CHILL_MIN_WIND_KPH {
+        return temp_c;
+    }
+    let v = wind_kph.powf(0.16);
+    13.12 + 0.6215 * temp_c - 11.37 * v + 0.3965 * temp_c * v
+}
+
+/// Heat index in °C, using the NWS Rothfusz regression
+///
+/// The regression works in °F, so the temperature is converted there and back.
+/// Returns `temp_c` unchanged below 80°F (26.7°C), where it doesn't apply.
+/// ```
+/// # use weather_dashboard::weather_math::heat_index_c;
+/// // NWS chart: 90°F at 70% humidity feels like 106°F (41.1°C)
+/// assert!((heat_index_c(32.22, 70) - 41.1).abs() < 0.3);
+/// // 96°F at 50% -> 108°F (42.2°C)
+/// assert!((heat_index_c(35.56, 50) - 42.2).abs() < 0.3);
+/// // Too cool for the formula
+/// assert_eq!(heat_index_c(20.0, 90), 20.0);
+/// ```
+pub fn heat_index_c(temp_c: f64, humidity: u8) -> f64 {
+    let t = temp_c * 9.0 / 5.0 + 32.0;
+    if t < HEAT_INDEX_MIN_TEMP_F {
+        return temp_c;
+    }
+    let rh = f64::from(humidity);
+    let hi = -42.379 + 2.04901523 * t + 10.14333127 * rh
+        - 0.22475541 * t * rh
+        - 0.00683783 * t * t
+        - 0.05481717 * rh * rh
+        + 0.00122874 * t * t * rh
+        + 0.00085282 * t * rh * rh
+        - 0.00000199 * t * t * rh * rh;
+    (hi - 32.0) * 5.0 / 9.0
+}
+
+/// Feels-like for a reading, in the units it was fetched in
+///
+/// Wind chill when it's cold and windy, the heat index when it's hot,
+/// otherwise just the temperature.
+pub fn computed_feels_like(weather: &WeatherData, units: impl Into<UnitPrefs>) -> f64 {
+    let units = units.into();
+    let temp_c = units.temp.to_celsius(weather.temperature);
+    let wind_kph = units.wind.to_kph(weather.wind_speed);
+
+    let feels_c = if temp_c <= WIND_CHILL_MAX_TEMP_C {
+        wind_chill_c(temp_c, wind_kph)
+    } else {
+        heat_index_c(temp_c, *weather.humidity)
+    };
+
+    units.temp.from_celsius(feels_c)
+}